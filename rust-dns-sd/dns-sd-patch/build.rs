@@ -45,8 +45,11 @@ fn main() {
         return;
     }
 
-    // === Linux 使用 avahi-compat-libdns_sd ===
+    // === Linux 預設使用純 Rust 的 mDNS，啟用 `avahi` feature 時才連結 avahi-compat-libdns_sd ===
     if target.contains("linux") {
+        if std::env::var_os("CARGO_FEATURE_AVAHI").is_none() {
+            return;
+        }
         pkg_config::Config::new()
             .statik(true)
             .probe("avahi-compat-libdns_sd")
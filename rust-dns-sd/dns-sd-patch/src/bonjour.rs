@@ -0,0 +1,139 @@
+use std::ptr::null;
+use std::ptr::null_mut;
+use std::ffi::CString;
+
+use {DNSError, ServiceError, TxtRecord};
+
+pub mod ffi {
+    use libc::c_void;
+    use libc::c_char;
+
+    pub enum DNSService {}
+    pub type DNSServiceRef = *mut DNSService;
+
+    // 以 i32 接收錯誤碼：C 端可能回傳 enum 以外的值，直接轉成 Rust enum 是未定義行為
+    pub type DNSServiceErrorType = i32;
+
+    pub type DNSServiceFlags = u32;
+    pub type DNSServiceRegisterReply = Option<extern "C" fn(DNSServiceRef,
+                                                            DNSServiceFlags,
+                                                            DNSServiceErrorType,
+                                                            *const c_char,
+                                                            *const c_char,
+                                                            *const c_char,
+                                                            *mut c_void)
+                                                           >;
+
+    extern "C" {
+        pub fn DNSServiceRegister(sdRef: *mut DNSServiceRef,
+                                  flags: DNSServiceFlags,
+                                  interfaceIndex: u32,
+                                  name: *const c_char,
+                                  regtype: *const c_char,
+                                  domain: *const c_char,
+                                  host: *const c_char,
+                                  port: u16,
+                                  txtLen: u16,
+                                  txtRecord: *const u8,
+                                  callBack: DNSServiceRegisterReply,
+                                  context: *mut c_void)
+                                  -> DNSServiceErrorType;
+
+        pub fn DNSServiceRefDeallocate(sdRef: DNSServiceRef);
+    }
+}
+
+#[derive(Debug)]
+pub struct DNSService {
+    sd_ref: ffi::DNSServiceRef,
+}
+
+fn c_string(field: &str, value: &str) -> Result<CString, DNSError> {
+    CString::new(value).map_err(|_| DNSError::InvalidArgument(format!("{} 不能包含 NUL 字元", field)))
+}
+
+impl DNSService {
+    pub fn register(name: Option<&str>,
+                    regtype: &str,
+                    domain: Option<&str>,
+                    host: Option<&str>,
+                    port: u16,
+                    txt: &[&str])
+                    -> std::result::Result<DNSService, DNSError> {
+        let txt = TxtRecord::from_strs(txt)?;
+        DNSService::register_with_txt(name, regtype, domain, host, port, &txt)
+    }
+
+    pub fn register_with_txt(name: Option<&str>,
+                             regtype: &str,
+                             domain: Option<&str>,
+                             host: Option<&str>,
+                             port: u16,
+                             txt: &TxtRecord)
+                             -> std::result::Result<DNSService, DNSError> {
+        let mut sd_ref: ffi::DNSServiceRef = null_mut();
+
+        // TxtRecord 已保證總長度不超過 u16
+        let txt_data = txt.to_bytes();
+
+        let name = match name {
+            Some(name) => Some(c_string("name", name)?),
+            None => None,
+        };
+        let regtype = c_string("regtype", regtype)?;
+        let domain = match domain {
+            Some(domain) => Some(c_string("domain", domain)?),
+            None => None,
+        };
+        let host = match host {
+            Some(host) => Some(c_string("host", host)?),
+            None => None,
+        };
+
+        let err = unsafe {
+            ffi::DNSServiceRegister(&mut sd_ref as *mut _,
+                                    0,
+                                    0,
+                                    name.as_ref().map_or(null(), |s| s.as_ptr()),
+                                    regtype.as_ptr(),
+                                    domain.as_ref().map_or(null(), |s| s.as_ptr()),
+                                    host.as_ref().map_or(null(), |s| s.as_ptr()),
+                                    port.to_be(),
+                                    txt_data.len() as u16,
+                                    if txt_data.is_empty() {
+                                        null()
+                                    } else {
+                                        txt_data.as_ptr()
+                                    },
+                                    None,
+                                    null_mut())
+        };
+
+        // We must be sure these stay are still alive during the DNSServiceRegister call
+        // Because we pass them as raw pointers, rust's borrow checker is useless there
+        // If they are still valid at this point, then we're good
+        drop(name);
+        drop(regtype);
+        drop(domain);
+        drop(host);
+        drop(txt_data);
+
+        match ServiceError::from_code(err) {
+            None if !sd_ref.is_null() => Ok(DNSService { sd_ref }),
+            None => Err(DNSError::Service(ServiceError::Unknown)),
+            Some(err) => Err(DNSError::Service(err)),
+        }
+    }
+}
+
+impl Drop for DNSService {
+    fn drop(&mut self) {
+        // 取消註冊並釋放 daemon 連線，只會執行一次
+        if !self.sd_ref.is_null() {
+            unsafe {
+                ffi::DNSServiceRefDeallocate(self.sd_ref);
+            }
+            self.sd_ref = null_mut();
+        }
+    }
+}
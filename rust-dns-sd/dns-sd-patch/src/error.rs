@@ -0,0 +1,134 @@
+use std::fmt;
+
+macro_rules! service_errors {
+    ($($name:ident = $code:expr,)*) => {
+        /// `DNSServiceErrorType` 的錯誤碼。
+        /// C 端可能回傳此列表以外的值，因此一律經過 `from_code` 檢查後才轉換。
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        pub enum ServiceError {
+            $($name,)*
+            /// 未列在 dns_sd.h 中的錯誤碼
+            Other(i32),
+        }
+
+        impl ServiceError {
+            /// 轉換 C 回傳的錯誤碼，`kDNSServiceErr_NoError`（0）回傳 `None`
+            pub fn from_code(code: i32) -> Option<ServiceError> {
+                match code {
+                    0 => None,
+                    $($code => Some(ServiceError::$name),)*
+                    other => Some(ServiceError::Other(other)),
+                }
+            }
+
+            pub fn code(&self) -> i32 {
+                match *self {
+                    $(ServiceError::$name => $code,)*
+                    ServiceError::Other(code) => code,
+                }
+            }
+        }
+    };
+}
+
+service_errors! {
+    Unknown = -65537,
+    NoSuchName = -65538,
+    NoMemory = -65539,
+    BadParam = -65540,
+    BadReference = -65541,
+    BadState = -65542,
+    BadFlags = -65543,
+    Unsupported = -65544,
+    NotInitialized = -65545,
+    AlreadyRegistered = -65547,
+    NameConflict = -65548,
+    Invalid = -65549,
+    Firewall = -65550,
+    Incompatible = -65551,
+    BadInterfaceIndex = -65552,
+    Refused = -65553,
+    NoSuchRecord = -65554,
+    NoAuth = -65555,
+    NoSuchKey = -65556,
+    NATTraversal = -65557,
+    DoubleNAT = -65558,
+    BadTime = -65559,
+    BadSig = -65560,
+    BadKey = -65561,
+    Transient = -65562,
+    ServiceNotRunning = -65563,
+    NATPortMappingUnsupported = -65564,
+    NATPortMappingDisabled = -65565,
+    NoRouter = -65566,
+    PollingMode = -65567,
+    Timeout = -65568,
+}
+
+#[derive(Debug)]
+pub enum DNSError {
+    /// DNS-SD 服務回傳的錯誤
+    Service(ServiceError),
+    /// 參數不合法，例如字串中含有 NUL 或 TXT 紀錄過長
+    InvalidArgument(String),
+    /// 純 Rust mDNS responder 的錯誤
+    #[cfg(all(target_os = "linux", not(feature = "avahi")))]
+    Mdns(::mdns_sd::Error),
+}
+
+impl fmt::Display for DNSError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DNSError::Service(ref err) => {
+                write!(format, "DNS-SD Error: {:?} ({})", err, err.code())
+            }
+            DNSError::InvalidArgument(ref message) => {
+                write!(format, "DNS-SD Error: invalid argument: {}", message)
+            }
+            #[cfg(all(target_os = "linux", not(feature = "avahi")))]
+            DNSError::Mdns(ref err) => write!(format, "DNS-SD Error: {}", err),
+        }
+    }
+}
+
+impl ::std::error::Error for DNSError {
+    fn source(&self) -> Option<&(dyn (::std::error::Error) + 'static)> {
+        match *self {
+            #[cfg(all(target_os = "linux", not(feature = "avahi")))]
+            DNSError::Mdns(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ServiceError> for DNSError {
+    fn from(err: ServiceError) -> DNSError {
+        DNSError::Service(err)
+    }
+}
+
+#[cfg(all(target_os = "linux", not(feature = "avahi")))]
+impl From<::mdns_sd::Error> for DNSError {
+    fn from(err: ::mdns_sd::Error) -> DNSError {
+        DNSError::Mdns(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServiceError;
+
+    #[test]
+    fn known_codes_round_trip() {
+        assert_eq!(ServiceError::from_code(0), None);
+        assert_eq!(ServiceError::from_code(-65548), Some(ServiceError::NameConflict));
+        assert_eq!(ServiceError::NameConflict.code(), -65548);
+    }
+
+    #[test]
+    fn unknown_codes_are_preserved() {
+        // -65546 在 dns_sd.h 中沒有定義
+        assert_eq!(ServiceError::from_code(-65546), Some(ServiceError::Other(-65546)));
+        assert_eq!(ServiceError::from_code(42).map(|e| e.code()), Some(42));
+    }
+}
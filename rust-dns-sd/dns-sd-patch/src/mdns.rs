@@ -0,0 +1,176 @@
+use mdns_sd::{ServiceDaemon, ServiceInfo, TxtProperty};
+
+use {DNSError, TxtRecord};
+
+// 沒有指定名稱或主機時使用的預設值
+const FALLBACK_HOSTNAME: &str = "aoede";
+
+/// 純 Rust 的 mDNS 服務註冊，行為對應 `DNSServiceRegister`。
+/// 服務會在 `DNSService` 被 drop 時取消註冊。
+pub struct DNSService {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl std::fmt::Debug for DNSService {
+    fn fmt(&self, format: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        format.debug_struct("DNSService")
+              .field("fullname", &self.fullname)
+              .finish()
+    }
+}
+
+fn local_hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| FALLBACK_HOSTNAME.to_string())
+}
+
+// 把 TxtRecord 轉成 mdns-sd 的屬性
+fn txt_properties(txt: &TxtRecord) -> Vec<TxtProperty> {
+    txt.iter()
+       .map(|(key, value)| match value {
+           Some(value) => TxtProperty::from((key, value)),
+           None => TxtProperty::from(key),
+       })
+       .collect()
+}
+
+impl DNSService {
+    pub fn register(name: Option<&str>,
+                    regtype: &str,
+                    domain: Option<&str>,
+                    host: Option<&str>,
+                    port: u16,
+                    txt: &[&str])
+                    -> std::result::Result<DNSService, DNSError> {
+        let txt = TxtRecord::from_strs(txt)?;
+        DNSService::register_with_txt(name, regtype, domain, host, port, &txt)
+    }
+
+    pub fn register_with_txt(name: Option<&str>,
+                             regtype: &str,
+                             domain: Option<&str>,
+                             host: Option<&str>,
+                             port: u16,
+                             txt: &TxtRecord)
+                             -> std::result::Result<DNSService, DNSError> {
+        let daemon = ServiceDaemon::new()?;
+        DNSService::register_on(daemon, name, regtype, domain, host, port, txt)
+    }
+
+    fn register_on(daemon: ServiceDaemon,
+                   name: Option<&str>,
+                   regtype: &str,
+                   domain: Option<&str>,
+                   host: Option<&str>,
+                   port: u16,
+                   txt: &TxtRecord)
+                   -> std::result::Result<DNSService, DNSError> {
+        let hostname = local_hostname();
+
+        let name = name.map(str::to_string).unwrap_or_else(|| hostname.clone());
+        let domain = domain.unwrap_or("local").trim_end_matches('.');
+        let ty_domain = format!("{}.{}.", regtype.trim_end_matches('.'), domain);
+        let host = match host {
+            Some(host) if host.ends_with('.') => host.to_string(),
+            Some(host) => format!("{}.", host),
+            None => format!("{}.{}.", hostname, domain),
+        };
+
+        // 位址交給 mdns-sd 依照網路介面自動填入
+        let info = ServiceInfo::new(&ty_domain, &name, &host, "", port, txt_properties(txt))?
+            .enable_addr_auto();
+        let fullname = info.get_fullname().to_string();
+
+        daemon.register(info)?;
+
+        Ok(DNSService {
+            daemon,
+            fullname,
+        })
+    }
+}
+
+impl Drop for DNSService {
+    fn drop(&mut self) {
+        if let Ok(receiver) = self.daemon.unregister(&self.fullname) {
+            // 等待 goodbye 封包送出，避免其他裝置繼續看到這個服務
+            let _ = receiver.recv_timeout(std::time::Duration::from_secs(1));
+        }
+        let _ = self.daemon.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use mdns_sd::{IfKind, ServiceDaemon, ServiceEvent};
+
+    use super::DNSService;
+    use TxtRecord;
+
+    const REGTYPE: &str = "_spotify-connect._tcp";
+
+    fn loopback_daemon() -> ServiceDaemon {
+        let daemon = ServiceDaemon::new().unwrap();
+        daemon.disable_interface(IfKind::All).unwrap();
+        daemon.enable_interface(IfKind::LoopbackV4).unwrap();
+        daemon
+    }
+
+    #[test]
+    fn txt_properties_split_on_first_equals() {
+        let txt = TxtRecord::from_strs(&["VERSION=1.0", "CPath=/a=b", "flag"]).unwrap();
+        let properties = super::txt_properties(&txt);
+
+        assert_eq!(properties[0].key(), "VERSION");
+        assert_eq!(properties[0].val_str(), "1.0");
+        assert_eq!(properties[1].val_str(), "/a=b");
+        assert_eq!(properties[2].key(), "flag");
+        assert_eq!(properties[2].val(), None);
+    }
+
+    #[test]
+    fn loopback_resolves_spotify_connect() {
+        let name = format!("aoede-test-{}", std::process::id());
+        let service = DNSService::register_on(loopback_daemon(),
+                                              Some(&name),
+                                              REGTYPE,
+                                              None,
+                                              Some("aoede-test.local"),
+                                              4070,
+                                              &TxtRecord::from_strs(&["VERSION=1.0", "CPath=/"]).unwrap())
+            .unwrap();
+
+        let browser = loopback_daemon();
+        let receiver = browser.browse(&format!("{}.local.", REGTYPE)).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut resolved = None;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match receiver.recv_timeout(remaining) {
+                Ok(ServiceEvent::ServiceResolved(info)) => {
+                    if info.get_fullname() == service.fullname {
+                        resolved = Some(info);
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+
+        let info = resolved.expect("沒有解析到註冊的 _spotify-connect._tcp 服務");
+        assert_eq!(info.get_port(), 4070);
+        assert_eq!(info.get_hostname(), "aoede-test.local.");
+        assert_eq!(info.get_property_val_str("VERSION"), Some("1.0"));
+        assert_eq!(info.get_property_val_str("CPath"), Some("/"));
+
+        drop(service);
+        let _ = browser.shutdown();
+    }
+}
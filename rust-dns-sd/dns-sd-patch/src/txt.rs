@@ -0,0 +1,118 @@
+use DNSError;
+
+// RFC 6763 §6.1：每個字串最長 255 位元組，整筆 TXT 紀錄不超過 65535 位元組
+const MAX_ENTRY_LEN: usize = 255;
+const MAX_RECORD_LEN: usize = 65535;
+
+/// 型別化的 TXT 紀錄建構器，會在加入時檢查 RFC 6763 的限制
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxtRecord {
+    entries: Vec<(String, Option<Vec<u8>>)>,
+}
+
+impl TxtRecord {
+    pub fn new() -> TxtRecord {
+        TxtRecord::default()
+    }
+
+    /// 從 `"key=value"` 或 `"key"` 格式的字串建立
+    pub fn from_strs(txt: &[&str]) -> Result<TxtRecord, DNSError> {
+        let mut record = TxtRecord::new();
+        for entry in txt {
+            match entry.find('=') {
+                Some(index) => record.insert(&entry[..index], Some(&entry.as_bytes()[index + 1..]))?,
+                None => record.insert(entry, None)?,
+            }
+        }
+        Ok(record)
+    }
+
+    /// 加入一個屬性；已存在的 key（不分大小寫）會被取代
+    pub fn insert(&mut self, key: &str, value: Option<&[u8]>) -> Result<(), DNSError> {
+        if key.is_empty() {
+            return Err(DNSError::InvalidArgument("TXT key 不能為空".to_string()));
+        }
+        if !key.bytes().all(|b| (0x20..=0x7e).contains(&b) && b != b'=') {
+            return Err(DNSError::InvalidArgument(format!("TXT key {:?} 只能包含可列印的 ASCII 字元且不能包含 '='", key)));
+        }
+
+        let entry_len = key.len() + value.map_or(0, |v| v.len() + 1);
+        if entry_len > MAX_ENTRY_LEN {
+            return Err(DNSError::InvalidArgument(format!("TXT 屬性 {} 超過 {} 位元組", key, MAX_ENTRY_LEN)));
+        }
+
+        let mut entries = self.entries.clone();
+        entries.retain(|(existing, _)| !existing.eq_ignore_ascii_case(key));
+        entries.push((key.to_string(), value.map(|v| v.to_vec())));
+
+        let record_len: usize = entries.iter()
+                                       .map(|(k, v)| 1 + k.len() + v.as_ref().map_or(0, |v| v.len() + 1))
+                                       .sum();
+        if record_len > MAX_RECORD_LEN {
+            return Err(DNSError::InvalidArgument(format!("TXT 紀錄超過 {} 位元組", MAX_RECORD_LEN)));
+        }
+
+        self.entries = entries;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<Option<&[u8]>> {
+        self.entries.iter()
+                    .find(|(existing, _)| existing.eq_ignore_ascii_case(key))
+                    .map(|(_, value)| value.as_ref().map(|v| v.as_slice()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&[u8]>)> {
+        self.entries.iter().map(|(key, value)| (key.as_str(), value.as_ref().map(|v| v.as_slice())))
+    }
+
+    /// DNS 線上格式：每個屬性前面加上一個長度位元組
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        for (key, value) in self.iter() {
+            let len = key.len() + value.map_or(0, |v| v.len() + 1);
+            data.push(len as u8);
+            data.extend_from_slice(key.as_bytes());
+            if let Some(value) = value {
+                data.push(b'=');
+                data.extend_from_slice(value);
+            }
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TxtRecord;
+
+    #[test]
+    fn encodes_length_prefixed_entries() {
+        let record = TxtRecord::from_strs(&["VERSION=1.0", "flag"]).unwrap();
+        assert_eq!(record.to_bytes(), b"\x0bVERSION=1.0\x04flag".to_vec());
+        assert_eq!(record.get("version"), Some(Some(&b"1.0"[..])));
+        assert_eq!(record.get("flag"), Some(None));
+    }
+
+    #[test]
+    fn replaces_duplicate_keys() {
+        let mut record = TxtRecord::new();
+        record.insert("CPath", Some(b"/a")).unwrap();
+        record.insert("cpath", Some(b"/b")).unwrap();
+        assert_eq!(record.iter().count(), 1);
+        assert_eq!(record.get("CPath"), Some(Some(&b"/b"[..])));
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        let mut record = TxtRecord::new();
+        assert!(record.insert("", None).is_err());
+        assert!(record.insert("a=b", None).is_err());
+        assert!(record.insert("key", Some(&[b'x'; 252])).is_err());
+        assert!(record.insert("key", Some(&[b'x'; 251])).is_ok());
+    }
+}
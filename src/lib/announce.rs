@@ -0,0 +1,91 @@
+///lib/announce.rs
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, Context, CreateEmbed, CreateEmbedFooter, CreateMessage, GuildId};
+
+use crate::lib::i18n::{self, tr};
+use crate::lib::metadata::ItemKind;
+use crate::lib::permissions::Capability;
+use crate::{Data, Error, PoiseContext};
+
+/// 伺服器的換歌公告設定，保存在 `GuildSettings` 中
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct AnnounceSettings {
+    pub channel_id: ChannelId,
+    pub enabled: bool,
+}
+
+/// 公告內容
+pub struct Announcement {
+    pub kind: ItemKind,
+    pub title: String,
+    // 曲目的演出者，或單集所屬的節目
+    pub artists: String,
+    pub album: Option<String>,
+    pub url: String,
+    pub duration_ms: u64,
+}
+
+fn announcement_embed(announcement: &Announcement) -> CreateEmbed {
+    let secs = announcement.duration_ms / 1000;
+    let mut description = announcement.artists.clone();
+    if let Some(ref album) = announcement.album {
+        description.push_str(&format!(" · {}", album));
+    }
+
+    CreateEmbed::new()
+        .title(format!("{} {}", announcement.kind.emoji(), announcement.title))
+        .url(&announcement.url)
+        .description(description)
+        .footer(CreateEmbedFooter::new(format!("{}:{:02}", secs / 60, secs % 60)))
+        .colour(0x1DB954)
+}
+
+/// 在伺服器的公告頻道貼出正在播放的曲目或單集，呼叫端負責只在換歌時呼叫
+pub async fn announce(ctx: &Context, data: &Data, guild_id: GuildId, announcement: &Announcement) {
+    let Some(settings) = data.settings.lock().await.get(guild_id).announce else {
+        return;
+    };
+    if !settings.enabled {
+        return;
+    }
+
+    let message = CreateMessage::new().embed(announcement_embed(announcement));
+    if let Err(e) = settings.channel_id.send_message(&ctx.http, message).await {
+        println!("[公告] ⚠️ 無法在 {} 發布公告: {:?}", settings.channel_id, e);
+    }
+}
+
+/// 開啟或關閉換歌公告
+#[poise::command(slash_command, guild_only, rename = "announce", custom_data = Capability::Admin)]
+pub async fn announce_command(
+    ctx: PoiseContext<'_>,
+    #[description = "是否在換歌時發布公告"] enabled: bool,
+    #[description = "公告頻道（預設為目前的頻道）"]
+    #[channel_types("Text")]
+    channel: Option<serenity::all::GuildChannel>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let lang = i18n::language(ctx).await;
+    let previous = ctx.data().settings.lock().await.get(guild_id).announce;
+    let channel_id = match channel {
+        Some(channel) => channel.id,
+        None => previous.map(|s| s.channel_id).unwrap_or_else(|| ctx.channel_id()),
+    };
+
+    ctx.data()
+        .settings
+        .lock()
+        .await
+        .update(guild_id, |settings| settings.announce = Some(AnnounceSettings { channel_id, enabled }))?;
+
+    let message = if enabled {
+        tr!(lang, "announce-enabled", channel = format!("<#{}>", channel_id))
+    } else {
+        tr!(lang, "announce-disabled")
+    };
+    ctx.say(message).await?;
+    Ok(())
+}
@@ -0,0 +1,334 @@
+///lib/audio_cache.rs
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use librespot::core::file_id::FileId;
+use librespot::core::session::Session;
+use librespot::core::spotify_id::{SpotifyId, SpotifyItemType};
+use librespot::metadata::{Episode, Metadata, Track};
+use serenity::all::CreateEmbed;
+
+use crate::lib::i18n::{self, tr};
+use crate::lib::permissions::OwnerOnly;
+use crate::{Data, Error, PoiseContext, say_ephemeral};
+
+// 使用量超過上限的這個比例後，檔案數減少才視為 librespot 清除了舊檔案
+const EVICTION_REPORT_RATIO: f64 = 0.9;
+
+/// 啟動以來的命中統計，重新啟動後歸零
+#[derive(Default, Debug)]
+pub struct AudioCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    // librespot 為了維持大小上限清除的檔案數（估計值）
+    pub evicted: u64,
+    // 上一次載入時快取中的檔案數，以及之後應該新增的檔案數
+    last_files: Option<u64>,
+    pending_writes: u64,
+}
+
+impl AudioCacheStats {
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+
+    /// 記錄一次載入，回傳推測被清除的檔案數
+    fn record(&mut self, hit: bool, usage: &CacheUsage, limit: Option<u64>) -> u64 {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+
+        // 未命中的檔案下載完成後才會寫入快取，用下一次載入時的檔案數推算
+        let near_limit = limit.is_some_and(|limit| usage.bytes as f64 >= limit as f64 * EVICTION_REPORT_RATIO);
+        let evicted = match self.last_files {
+            Some(last) if near_limit => (last + self.pending_writes).saturating_sub(usage.files),
+            _ => 0,
+        };
+        self.evicted += evicted;
+        self.last_files = Some(usage.files);
+        self.pending_writes = u64::from(!hit);
+        evicted
+    }
+
+    // 清除快取後重新開始推算
+    fn reset_usage(&mut self) {
+        self.last_files = None;
+        self.pending_writes = 0;
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct CacheUsage {
+    pub bytes: u64,
+    pub files: u64,
+}
+
+/// librespot 把音訊檔案存在 `<cache_dir>/<FileId 前 2 碼>/<其餘 38 碼>`
+fn audio_files(dir: &Path) -> Vec<(PathBuf, Option<FileId>, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files = Vec::new();
+    for prefix in entries.flatten() {
+        let prefix_name = prefix.file_name().to_string_lossy().to_string();
+        if prefix_name.len() != 2 || !prefix_name.chars().all(|c| c.is_ascii_hexdigit()) {
+            continue;
+        }
+        let Ok(children) = fs::read_dir(prefix.path()) else {
+            continue;
+        };
+        for file in children.flatten() {
+            let Ok(metadata) = file.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let name = format!("{}{}", prefix_name, file.file_name().to_string_lossy());
+            files.push((file.path(), parse_file_id(&name), metadata.len()));
+        }
+    }
+    files
+}
+
+fn parse_file_id(name: &str) -> Option<FileId> {
+    // 先確認是 ASCII，多位元組字元會讓下面的切片落在字元中間
+    if name.len() != 40 || !name.is_ascii() {
+        return None;
+    }
+    let bytes = (0..name.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&name[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(FileId::from_raw(&bytes))
+}
+
+/// 計算快取目錄中音訊檔案的大小與數量
+pub async fn usage(cache_dir: Option<String>) -> CacheUsage {
+    let Some(dir) = cache_dir else {
+        return CacheUsage::default();
+    };
+    tokio::task::spawn_blocking(move || {
+        audio_files(Path::new(&dir))
+            .iter()
+            .fold(CacheUsage::default(), |usage, (_, _, len)| CacheUsage {
+                bytes: usage.bytes + len,
+                files: usage.files + 1,
+            })
+    })
+    .await
+    .unwrap_or_default()
+}
+
+async fn file_ids(session: &Session, id: &SpotifyId) -> Result<Vec<FileId>, librespot::core::Error> {
+    if id.item_type == SpotifyItemType::Episode {
+        let episode = Episode::get(session, id).await?;
+        Ok(episode.audio.values().copied().collect())
+    } else {
+        let track = Track::get(session, id).await?;
+        Ok(track.files.values().copied().collect())
+    }
+}
+
+/// 曲目開始載入時檢查音訊檔案是否已經在快取中，並記錄命中與清除的情況
+pub async fn on_loading(data: Data, session: Session, track_id: SpotifyId) {
+    let Some(cache) = session.cache() else {
+        return;
+    };
+    let files = match file_ids(&session, &track_id).await {
+        Ok(files) => files,
+        Err(e) => {
+            println!("[快取] ⚠️ 無法取得音訊檔案列表: {:?}", e);
+            return;
+        }
+    };
+    // 只檢查檔案是否存在，不透過 `Cache::file` 開啟，避免改變 librespot 的清除順序
+    let hit = files
+        .iter()
+        .any(|file| cache.file_path(*file).is_some_and(|path| path.exists()));
+
+    let limit = data.config.audio_cache_limit();
+    let cache_dir = data.player.lock().await.cache_dir();
+    let usage = usage(cache_dir).await;
+    let evicted = data.audio_cache.lock().await.record(hit, &usage, limit);
+
+    let id = track_id.to_base62().unwrap_or_default();
+    if hit {
+        println!("[快取] ✓ 命中 {}（{} 個檔案，{}）", id, usage.files, format_bytes(usage.bytes));
+    } else {
+        println!("[快取] 未命中 {}，將從 Spotify 下載（{} 個檔案，{}）", id, usage.files, format_bytes(usage.bytes));
+    }
+    if evicted > 0 {
+        println!("[快取] 🧹 已達大小上限，librespot 清除了 {} 個最久沒播放的檔案", evicted);
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// 管理 Spotify 音訊檔案快取
+#[poise::command(slash_command, subcommands("cache_stats", "cache_clear"))]
+pub async fn cache(_ctx: PoiseContext<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 顯示音訊檔案快取的使用量與命中率
+#[poise::command(slash_command, rename = "stats")]
+pub async fn cache_stats(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let data = ctx.data();
+
+    let cache_dir = data.player.lock().await.cache_dir();
+    let usage = usage(cache_dir).await;
+    let limit = data.config.audio_cache_limit();
+
+    let size = match limit {
+        Some(limit) => tr!(
+            lang,
+            "cache-size-limited",
+            size = format_bytes(usage.bytes),
+            limit = format_bytes(limit),
+            percent = format!("{:.1}", usage.bytes as f64 / limit as f64 * 100.0)
+        ),
+        None => tr!(lang, "cache-size-unlimited", size = format_bytes(usage.bytes)),
+    };
+    let (hits, misses, evicted, hit_rate) = {
+        let stats = data.audio_cache.lock().await;
+        (stats.hits, stats.misses, stats.evicted, stats.hit_rate())
+    };
+    let hit_rate = match hit_rate {
+        Some(rate) => tr!(lang, "cache-hit-rate", percent = format!("{:.1}", rate * 100.0), hits = hits, misses = misses),
+        None => tr!(lang, "cache-hit-rate-none"),
+    };
+
+    let embed = CreateEmbed::new()
+        .title(tr!(lang, "cache-title"))
+        .field(tr!(lang, "cache-field-size"), size, true)
+        .field(tr!(lang, "cache-field-files"), usage.files.to_string(), true)
+        .field(tr!(lang, "cache-field-hit-rate"), hit_rate, false)
+        .field(tr!(lang, "cache-field-evicted"), evicted.to_string(), true)
+        .footer(serenity::all::CreateEmbedFooter::new(tr!(lang, "cache-footer")));
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// 刪除所有快取的音訊檔案（擁有者）
+#[poise::command(slash_command, rename = "clear", custom_data = OwnerOnly)]
+pub async fn cache_clear(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let data = ctx.data();
+
+    let (cache_dir, cache) = {
+        let player = data.player.lock().await;
+        (player.cache_dir(), player.session.cache().cloned())
+    };
+    let Some(dir) = cache_dir else {
+        return say_ephemeral(ctx, tr!(lang, "cache-disabled")).await;
+    };
+
+    let (removed, failed, bytes) = tokio::task::spawn_blocking(move || {
+        let mut removed = 0u64;
+        let mut failed = 0u64;
+        let mut bytes = 0u64;
+        for (path, file_id, len) in audio_files(Path::new(&dir)) {
+            // 透過 librespot 刪除，讓它的大小上限計算保持正確
+            let result = match (&cache, file_id) {
+                (Some(cache), Some(file_id)) => cache.remove_file(file_id).map_err(|e| e.to_string()),
+                _ => fs::remove_file(&path).map_err(|e| e.to_string()),
+            };
+            match result {
+                Ok(()) => {
+                    removed += 1;
+                    bytes += len;
+                }
+                Err(e) => {
+                    println!("[快取] ⚠️ 無法刪除 {:?}: {}", path, e);
+                    failed += 1;
+                }
+            }
+        }
+        (removed, failed, bytes)
+    })
+    .await?;
+
+    data.audio_cache.lock().await.reset_usage();
+    println!("[快取] 🗑️ 已刪除 {} 個音訊檔案（{}），失敗 {} 個", removed, format_bytes(bytes), failed);
+
+    let message = if failed > 0 {
+        tr!(lang, "cache-cleared-partial", count = removed, size = format_bytes(bytes), failed = failed)
+    } else {
+        tr!(lang, "cache-cleared", count = removed, size = format_bytes(bytes))
+    };
+    say_ephemeral(ctx, message).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(bytes: u64, files: u64) -> CacheUsage {
+        CacheUsage { bytes, files }
+    }
+
+    #[test]
+    fn hit_rate_counts_hits_and_misses() {
+        let mut stats = AudioCacheStats::default();
+        assert_eq!(stats.hit_rate(), None);
+        stats.record(true, &usage(0, 0), None);
+        stats.record(false, &usage(0, 0), None);
+        stats.record(true, &usage(0, 0), None);
+        stats.record(true, &usage(0, 0), None);
+        assert_eq!(stats.hit_rate(), Some(0.75));
+    }
+
+    #[test]
+    fn eviction_is_reported_only_near_the_limit() {
+        let mut stats = AudioCacheStats::default();
+        // 遠低於上限時檔案數減少（例如跳過的曲目沒有寫入）不算清除
+        stats.record(false, &usage(100, 10), Some(1000));
+        assert_eq!(stats.record(false, &usage(100, 9), Some(1000)), 0);
+
+        // 接近上限時，上一首下載的檔案加入後總數反而減少
+        stats.record(false, &usage(950, 10), Some(1000));
+        assert_eq!(stats.record(true, &usage(950, 9), Some(1000)), 2);
+        assert_eq!(stats.evicted, 2);
+
+        // 沒有上限時不會清除
+        let mut unlimited = AudioCacheStats::default();
+        unlimited.record(false, &usage(950, 10), None);
+        assert_eq!(unlimited.record(false, &usage(950, 5), None), 0);
+    }
+
+    #[test]
+    fn file_ids_are_parsed_from_cache_paths() {
+        let name = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(parse_file_id(name).map(|id| id.to_base16().unwrap()), Some(name.to_string()));
+        assert!(parse_file_id("0123").is_none());
+        assert!(parse_file_id("zz23456789abcdef0123456789abcdef01234567").is_none());
+    }
+
+    #[test]
+    fn non_ascii_file_names_are_ignored() {
+        // 40 bytes，但「é」佔兩個 bytes，奇數位置會落在字元中間
+        let name = "0é23456789abcdef0123456789abcdef0123456";
+        assert_eq!(name.len(), 40);
+        assert!(parse_file_id(name).is_none());
+    }
+}
@@ -0,0 +1,64 @@
+///lib/auth.rs
+use serenity::all::UserId;
+
+use crate::lib::i18n::{self, tr};
+use crate::lib::permissions::OwnerOnly;
+use crate::lib::player::{AuthState, SpotifyPlayer};
+use crate::lib::voice::user_voice_channel;
+use crate::{Error, PoiseContext, say_ephemeral};
+
+/// 透過 Spotify Connect 的 Discovery 重新認證 Spotify 帳號
+#[poise::command(slash_command, custom_data = OwnerOnly)]
+pub async fn spotify_login(
+    ctx: PoiseContext<'_>,
+    #[description = "已經有憑證時也重新認證（例如更換帳號）"] force: Option<bool>,
+) -> Result<(), Error> {
+    // 關閉 Spirc 可能超過 Discord 的 3 秒回應期限
+    ctx.defer_ephemeral().await?;
+    let lang = i18n::language(ctx).await;
+    let player = ctx.data().player.clone();
+
+    let (device_name, cache_dir) = {
+        let mut player = player.lock().await;
+        if !player.begin_auth(force.unwrap_or(false)) {
+            let message = match player.auth_state() {
+                AuthState::Authenticating => tr!(lang, "auth-in-progress"),
+                _ => tr!(lang, "auth-already-authenticated"),
+            };
+            drop(player);
+            return say_ephemeral(ctx, message).await;
+        }
+        (player.device_name.clone(), player.cache_dir())
+    };
+    // Discovery 使用相同的裝置名稱，先關閉目前的 Spirc
+    ctx.data().connect.disable().await;
+
+    say_ephemeral(ctx, tr!(lang, "auth-started", device = device_name.as_str())).await?;
+
+    let result = SpotifyPlayer::re_auth(cache_dir, &device_name).await;
+    if let Err(ref e) = result {
+        println!("[認證] ✗ 認證失敗: {:?}", e);
+    }
+    let authenticated = result.is_ok();
+    let has_credentials = {
+        let mut player = player.lock().await;
+        player.finish_auth(result.ok());
+        player.is_authenticated()
+    };
+
+    // 使用者已經在語音頻道中時，用新的或還原的憑證重新啟用 Spotify Connect
+    let user_id = UserId::new(ctx.data().config.discord_user_id);
+    if has_credentials && user_voice_channel(ctx.serenity_context(), user_id).is_some() {
+        ctx.data().connect.restart().await;
+    }
+
+    let message = match (authenticated, has_credentials) {
+        (true, _) => {
+            println!("[認證] ✓ Spotify 認證完成");
+            "auth-done"
+        }
+        (false, true) => "auth-failed-kept",
+        (false, false) => "auth-failed",
+    };
+    say_ephemeral(ctx, tr!(lang, message)).await
+}
@@ -4,6 +4,8 @@ use figment::{
 };
 use serde::Deserialize;
 
+use crate::lib::settings::Language;
+
 #[derive(Deserialize, Clone)]
 pub struct Config {
     #[serde(alias = "DISCORD_TOKEN")]
@@ -19,6 +21,38 @@ pub struct Config {
     #[serde(alias = "CACHE_DIR")]
     #[serde(default = "default_cache_dir")]
     pub cache_dir: String,
+    // 暫停或頻道中沒有其他人超過 N 分鐘後離開語音頻道，0 表示停用
+    #[serde(alias = "IDLE_TIMEOUT_MINUTES")]
+    #[serde(default = "default_idle_timeout_minutes")]
+    pub idle_timeout_minutes: u64,
+    // 閒置超過 M 分鐘後關閉 Spotify Connect，0 表示停用
+    #[serde(alias = "CONNECT_IDLE_TIMEOUT_MINUTES")]
+    #[serde(default = "default_connect_idle_timeout_minutes")]
+    pub connect_idle_timeout_minutes: u64,
+    // 離開語音頻道前多久發出警告
+    #[serde(alias = "IDLE_WARNING_SECONDS")]
+    #[serde(default = "default_idle_warning_seconds")]
+    pub idle_warning_seconds: u64,
+    // 頻道中只剩機器人時自動暫停，有人回來時是否自動繼續播放
+    #[serde(alias = "RESUME_ON_RETURN")]
+    #[serde(default = "default_false")]
+    pub resume_on_return: bool,
+    // 回報語音連線錯誤的文字頻道，未設定時只輸出到終端機
+    #[serde(alias = "LOG_CHANNEL_ID")]
+    #[serde(default)]
+    pub log_channel_id: Option<u64>,
+    // 投票跳過或暫停需要頻道中多少百分比的聽眾同意（1 到 100）
+    #[serde(alias = "VOTE_THRESHOLD_PERCENT")]
+    #[serde(default = "default_vote_threshold_percent")]
+    pub vote_threshold_percent: u64,
+    // 音訊檔案快取的大小上限（MB），超過時 librespot 會刪除最久沒用的檔案，0 表示不限制
+    #[serde(alias = "AUDIO_CACHE_SIZE_MB")]
+    #[serde(default = "default_audio_cache_size_mb")]
+    pub audio_cache_size_mb: u64,
+    // 伺服器沒有設定語言時使用的語言（zh-TW 或 en）
+    #[serde(alias = "BOT_LANGUAGE")]
+    #[serde(default)]
+    pub language: Language,
 }
 fn default_false() -> bool {
     false
@@ -31,12 +65,55 @@ fn default_cache_dir() -> String {
     "cache".to_string()
 }
 
+fn default_idle_timeout_minutes() -> u64 {
+    10
+}
+
+fn default_connect_idle_timeout_minutes() -> u64 {
+    30
+}
+
+fn default_idle_warning_seconds() -> u64 {
+    60
+}
+
+fn default_vote_threshold_percent() -> u64 {
+    50
+}
+
+fn default_audio_cache_size_mb() -> u64 {
+    4096
+}
+
 impl Config {
+    /// 音訊檔案快取的大小上限（bytes），`None` 表示不限制
+    pub fn audio_cache_limit(&self) -> Option<u64> {
+        match self.audio_cache_size_mb {
+            0 => None,
+            mb => Some(mb * 1024 * 1024),
+        }
+    }
+
     pub fn new() -> Result<Self, Box<Error>> {
-        let config: Config = Figment::new()
+        let mut config: Config = Figment::new()
             .merge(Toml::file("config.toml"))
-            .merge(Env::raw())
+            .merge(
+                Env::raw()
+                    // LANGUAGE 是系統語系變數（例如 `en_US:en`），不是這個設定
+                    .ignore(&["LANGUAGE"])
+                    .map(|key| if key == "bot_language" { "language".into() } else { key.into() }),
+            )
             .extract()?;
+
+        // 超過 100% 的門檻永遠無法通過，0% 則不需要任何人同意
+        let threshold = config.vote_threshold_percent.clamp(1, 100);
+        if threshold != config.vote_threshold_percent {
+            println!(
+                "⚠️ vote_threshold_percent 必須介於 1 到 100，已從 {} 改為 {}",
+                config.vote_threshold_percent, threshold
+            );
+            config.vote_threshold_percent = threshold;
+        }
         Ok(config)
     }
 }
@@ -0,0 +1,462 @@
+///lib/connect.rs
+use std::sync::Arc;
+
+use librespot::core::Error as LibrespotError;
+use serenity::async_trait;
+use tokio::sync::{mpsc, oneshot, watch, Mutex};
+
+use crate::lib::player::SpotifyPlayer;
+
+/// Spotify Connect 目前的狀態，`generation` 在每次成功啟用時加一
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConnectState {
+    Disabled,
+    Enabling,
+    Enabled { generation: u64 },
+    Disabling,
+    // 啟用失敗（例如尚未認證），下一次 enable 會再試
+    Failed,
+}
+
+impl ConnectState {
+    pub fn is_enabled(&self) -> bool {
+        matches!(self, ConnectState::Enabled { .. })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ConnectCommand {
+    Enable,
+    Disable,
+    // 關閉後重新啟用，已停用時等同 Enable
+    Restart,
+}
+
+struct Request {
+    command: ConnectCommand,
+    reply: Option<oneshot::Sender<ConnectState>>,
+}
+
+/// 透過 Spirc 控制播放的指令
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Playback {
+    Play,
+    Pause,
+    Next,
+}
+
+#[derive(Debug)]
+pub enum PlaybackError {
+    // Spotify Connect 尚未啟用
+    Unavailable,
+    Spirc(LibrespotError),
+}
+
+enum Message {
+    Lifecycle(Request),
+    Playback {
+        command: Playback,
+        reply: oneshot::Sender<Result<(), PlaybackError>>,
+    },
+}
+
+/// 實際啟用與停用 Spotify Connect 的對象，測試時換成假的實作
+#[async_trait]
+pub trait ConnectBackend: Send + 'static {
+    /// 成功時回傳 true
+    async fn enable(&mut self) -> bool;
+    async fn disable(&mut self);
+    async fn playback(&mut self, command: Playback) -> Result<(), PlaybackError>;
+}
+
+#[async_trait]
+impl ConnectBackend for Arc<Mutex<SpotifyPlayer>> {
+    async fn enable(&mut self) -> bool {
+        self.lock().await.enable_connect().await
+    }
+
+    async fn disable(&mut self) {
+        self.lock().await.disable_connect().await;
+    }
+
+    async fn playback(&mut self, command: Playback) -> Result<(), PlaybackError> {
+        let player = self.lock().await;
+        let Some(ref spirc) = player.spirc else {
+            return Err(PlaybackError::Unavailable);
+        };
+        match command {
+            Playback::Play => spirc.play(),
+            Playback::Pause => spirc.pause(),
+            Playback::Next => spirc.next(),
+        }
+        .map_err(PlaybackError::Spirc)
+    }
+}
+
+/// 傳送指令給 Spirc 的 actor，啟用、停用與播放控制都在同一個 task 中依序執行
+#[derive(Clone)]
+pub struct ConnectHandle {
+    sender: mpsc::UnboundedSender<Message>,
+    state: watch::Receiver<ConnectState>,
+}
+
+impl ConnectHandle {
+    pub fn spawn(backend: impl ConnectBackend) -> ConnectHandle {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (state_tx, state) = watch::channel(ConnectState::Disabled);
+        tokio::spawn(run(backend, receiver, state_tx));
+        ConnectHandle { sender, state }
+    }
+
+    pub fn state(&self) -> ConnectState {
+        *self.state.borrow()
+    }
+
+    /// 訂閱狀態變化
+    pub fn subscribe(&self) -> watch::Receiver<ConnectState> {
+        self.state.clone()
+    }
+
+    /// 啟用並等待結果
+    pub async fn enable(&self) -> ConnectState {
+        self.send(ConnectCommand::Enable).await
+    }
+
+    /// 停用並等待結果
+    pub async fn disable(&self) -> ConnectState {
+        self.send(ConnectCommand::Disable).await
+    }
+
+    /// 重新啟用並等待結果
+    pub async fn restart(&self) -> ConnectState {
+        self.send(ConnectCommand::Restart).await
+    }
+
+    pub async fn play(&self) -> Result<(), PlaybackError> {
+        self.playback(Playback::Play).await
+    }
+
+    pub async fn pause(&self) -> Result<(), PlaybackError> {
+        self.playback(Playback::Pause).await
+    }
+
+    pub async fn next(&self) -> Result<(), PlaybackError> {
+        self.playback(Playback::Next).await
+    }
+
+    async fn send(&self, command: ConnectCommand) -> ConnectState {
+        let (reply, result) = oneshot::channel();
+        let request = Request { command, reply: Some(reply) };
+        if self.sender.send(Message::Lifecycle(request)).is_err() {
+            return self.state();
+        }
+        result.await.unwrap_or_else(|_| self.state())
+    }
+
+    async fn playback(&self, command: Playback) -> Result<(), PlaybackError> {
+        let (reply, result) = oneshot::channel();
+        if self.sender.send(Message::Playback { command, reply }).is_err() {
+            return Err(PlaybackError::Unavailable);
+        }
+        result.await.unwrap_or(Err(PlaybackError::Unavailable))
+    }
+
+    // 不等待結果，測試用來模擬連續的加入與離開
+    #[cfg(test)]
+    fn request(&self, command: ConnectCommand) {
+        let _ = self.sender.send(Message::Lifecycle(Request { command, reply: None }));
+    }
+}
+
+// 合併排隊中的指令：只有最後的目標狀態有意義，中間的 Restart 仍會保留
+fn coalesce(commands: &[ConnectCommand]) -> (bool, bool) {
+    let mut enable = false;
+    let mut restart = false;
+    for command in commands {
+        match command {
+            ConnectCommand::Enable => enable = true,
+            ConnectCommand::Restart => {
+                enable = true;
+                restart = true;
+            }
+            ConnectCommand::Disable => {
+                enable = false;
+                restart = false;
+            }
+        }
+    }
+    (enable, restart)
+}
+
+async fn run(
+    mut backend: impl ConnectBackend,
+    mut receiver: mpsc::UnboundedReceiver<Message>,
+    state: watch::Sender<ConnectState>,
+) {
+    let mut generation = 0;
+    // 合併生命週期指令時遇到的播放指令，留到下一輪處理以維持順序
+    let mut pending = None;
+
+    loop {
+        let message = match pending.take() {
+            Some(message) => message,
+            None => match receiver.recv().await {
+                Some(message) => message,
+                None => break,
+            },
+        };
+        let request = match message {
+            Message::Lifecycle(request) => request,
+            Message::Playback { command, reply } => {
+                let _ = reply.send(backend.playback(command).await);
+                continue;
+            }
+        };
+
+        // 處理期間累積的指令一次合併，連續加入離開時不會反覆重建 Spirc
+        let mut requests = vec![request];
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                Message::Lifecycle(request) => requests.push(request),
+                playback => {
+                    pending = Some(playback);
+                    break;
+                }
+            }
+        }
+        let commands: Vec<ConnectCommand> = requests.iter().map(|r| r.command).collect();
+        let (enable, restart) = coalesce(&commands);
+
+        let current = *state.borrow();
+        if (!enable || restart) && current != ConnectState::Disabled {
+            state.send_replace(ConnectState::Disabling);
+            backend.disable().await;
+            state.send_replace(ConnectState::Disabled);
+        }
+        if enable && !state.borrow().is_enabled() {
+            state.send_replace(ConnectState::Enabling);
+            let next = if backend.enable().await {
+                generation += 1;
+                ConnectState::Enabled { generation }
+            } else {
+                ConnectState::Failed
+            };
+            state.send_replace(next);
+        }
+
+        let result = *state.borrow();
+        println!("[Connect] {:?} -> {:?}", commands, result);
+        for reply in requests.into_iter().filter_map(|r| r.reply) {
+            let _ = reply.send(result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    enum Call {
+        Enable,
+        Disable,
+        Playback(Playback),
+    }
+
+    #[derive(Clone, Default)]
+    struct FakeBackend {
+        calls: Arc<std::sync::Mutex<Vec<Call>>>,
+        fail: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    impl FakeBackend {
+        fn calls(&self) -> Vec<Call> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl ConnectBackend for FakeBackend {
+        async fn enable(&mut self) -> bool {
+            // 模擬建立 Session 與 Spirc 需要的時間
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.calls.lock().unwrap().push(Call::Enable);
+            !self.fail.load(std::sync::atomic::Ordering::SeqCst)
+        }
+
+        async fn disable(&mut self) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.calls.lock().unwrap().push(Call::Disable);
+        }
+
+        async fn playback(&mut self, command: Playback) -> Result<(), PlaybackError> {
+            let mut calls = self.calls.lock().unwrap();
+            // 和真正的 Spirc 一樣，停用時無法控制播放
+            let enabled = calls.iter().rev().find_map(|call| match call {
+                Call::Enable => Some(true),
+                Call::Disable => Some(false),
+                Call::Playback(_) => None,
+            });
+            if enabled != Some(true) {
+                return Err(PlaybackError::Unavailable);
+            }
+            calls.push(Call::Playback(command));
+            Ok(())
+        }
+    }
+
+    // 任何時候都不會連續啟用兩次而沒有先停用
+    fn assert_alternating(calls: &[Call]) {
+        let mut enabled = false;
+        for call in calls {
+            match call {
+                Call::Enable => {
+                    assert!(!enabled, "enabled twice without disabling: {:?}", calls);
+                    enabled = true;
+                }
+                Call::Disable => enabled = false,
+                Call::Playback(_) => {}
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn enable_and_disable_update_state() {
+        let backend = FakeBackend::default();
+        let connect = ConnectHandle::spawn(backend.clone());
+
+        assert_eq!(connect.enable().await, ConnectState::Enabled { generation: 1 });
+        assert_eq!(connect.state(), ConnectState::Enabled { generation: 1 });
+        // 已啟用時再次啟用不會重建
+        assert_eq!(connect.enable().await, ConnectState::Enabled { generation: 1 });
+        assert_eq!(connect.disable().await, ConnectState::Disabled);
+        assert_eq!(connect.disable().await, ConnectState::Disabled);
+
+        assert_eq!(backend.calls(), vec![Call::Enable, Call::Disable]);
+    }
+
+    #[tokio::test]
+    async fn restart_creates_a_new_generation() {
+        let backend = FakeBackend::default();
+        let connect = ConnectHandle::spawn(backend.clone());
+
+        assert_eq!(connect.restart().await, ConnectState::Enabled { generation: 1 });
+        assert_eq!(connect.restart().await, ConnectState::Enabled { generation: 2 });
+        assert_eq!(backend.calls(), vec![Call::Enable, Call::Disable, Call::Enable]);
+    }
+
+    #[tokio::test]
+    async fn failed_enable_is_retried() {
+        let backend = FakeBackend::default();
+        backend.fail.store(true, std::sync::atomic::Ordering::SeqCst);
+        let connect = ConnectHandle::spawn(backend.clone());
+
+        assert_eq!(connect.enable().await, ConnectState::Failed);
+        backend.fail.store(false, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(connect.enable().await, ConnectState::Enabled { generation: 1 });
+    }
+
+    #[tokio::test]
+    async fn rapid_join_leave_ends_in_last_requested_state() {
+        let backend = FakeBackend::default();
+        let connect = ConnectHandle::spawn(backend.clone());
+
+        for _ in 0..20 {
+            connect.request(ConnectCommand::Enable);
+            connect.request(ConnectCommand::Disable);
+        }
+        assert_eq!(connect.enable().await, ConnectState::Enabled { generation: 1 });
+
+        let calls = backend.calls();
+        assert_alternating(&calls);
+        // 排隊中的指令會被合併，不會每次加入離開都重建
+        assert!(calls.len() < 40, "commands were not coalesced: {:?}", calls);
+
+        for _ in 0..20 {
+            connect.request(ConnectCommand::Disable);
+            connect.request(ConnectCommand::Enable);
+        }
+        assert_eq!(connect.disable().await, ConnectState::Disabled);
+        assert_alternating(&backend.calls());
+        assert_eq!(backend.calls().last(), Some(&Call::Disable));
+    }
+
+    #[tokio::test]
+    async fn concurrent_callers_are_serialized() {
+        let backend = FakeBackend::default();
+        let connect = ConnectHandle::spawn(backend.clone());
+
+        let tasks: Vec<_> = (0..10)
+            .map(|i| {
+                let connect = connect.clone();
+                tokio::spawn(async move {
+                    if i % 2 == 0 {
+                        connect.enable().await
+                    } else {
+                        connect.disable().await
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_alternating(&backend.calls());
+        let state = connect.state();
+        assert!(
+            matches!(state, ConnectState::Enabled { .. } | ConnectState::Disabled),
+            "left in a transitional state: {:?}",
+            state
+        );
+    }
+
+    #[tokio::test]
+    async fn playback_runs_in_order_with_lifecycle_commands() {
+        let backend = FakeBackend::default();
+        let connect = ConnectHandle::spawn(backend.clone());
+
+        assert!(matches!(connect.pause().await, Err(PlaybackError::Unavailable)));
+
+        connect.request(ConnectCommand::Enable);
+        let (pause, next) = tokio::join!(connect.pause(), connect.next());
+        pause.unwrap();
+        next.unwrap();
+        connect.request(ConnectCommand::Disable);
+        assert!(matches!(connect.play().await, Err(PlaybackError::Unavailable)));
+
+        assert_eq!(
+            backend.calls(),
+            vec![
+                Call::Enable,
+                Call::Playback(Playback::Pause),
+                Call::Playback(Playback::Next),
+                Call::Disable
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn subscribers_see_transitions() {
+        let backend = FakeBackend::default();
+        let connect = ConnectHandle::spawn(backend.clone());
+        let mut state = connect.subscribe();
+
+        let waiter = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            while state.changed().await.is_ok() {
+                let current = *state.borrow_and_update();
+                seen.push(current);
+                if current.is_enabled() {
+                    break;
+                }
+            }
+            seen
+        });
+
+        connect.enable().await;
+        let seen = waiter.await.unwrap();
+        assert_eq!(seen.last(), Some(&ConnectState::Enabled { generation: 1 }));
+    }
+}
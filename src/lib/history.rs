@@ -0,0 +1,354 @@
+///lib/history.rs
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::lib::i18n::{self, tr};
+use crate::lib::metadata::ItemKind;
+use crate::lib::settings::Language;
+use crate::lib::storage::backup;
+use crate::{Error, PoiseContext};
+
+// 歷史紀錄最多保留的筆數，超過時丟棄最舊的紀錄
+const MAX_ENTRIES: usize = 10_000;
+const HISTORY_FILE: &str = "history.json";
+// Discord 單則訊息的字數上限
+const MESSAGE_LIMIT: usize = 2000;
+
+/// 一筆已播放曲目的紀錄
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    pub track_id: String,
+    pub title: String,
+    pub artists: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub listened_ms: u64,
+    // 舊的紀錄沒有這個欄位，視為一般曲目
+    #[serde(default)]
+    pub kind: ItemKind,
+}
+
+impl HistoryEntry {
+    pub fn spotify_url(&self) -> String {
+        format!("https://open.spotify.com/{}/{}", self.kind.url_path(), self.track_id)
+    }
+}
+
+// 正在播放中的曲目，結束時才寫入歷史紀錄
+struct CurrentTrack {
+    entry: HistoryEntry,
+    resumed_at: Option<Instant>,
+}
+
+impl CurrentTrack {
+    fn pause(&mut self) {
+        if let Some(resumed_at) = self.resumed_at.take() {
+            self.entry.listened_ms += resumed_at.elapsed().as_millis() as u64;
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct HistoryFile {
+    entries: Vec<HistoryEntry>,
+}
+
+pub struct HistoryStore {
+    path: Option<PathBuf>,
+    entries: Vec<HistoryEntry>,
+    current: Option<CurrentTrack>,
+}
+
+impl HistoryStore {
+    /// 從 `cache_dir` 載入播放紀錄，沒有快取目錄時只保存在記憶體中
+    pub fn load(cache_dir: Option<&str>) -> HistoryStore {
+        let path = cache_dir.map(|dir| Path::new(dir).join(HISTORY_FILE));
+
+        let entries = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => match serde_json::from_str::<HistoryFile>(&content) {
+                Ok(file) => file.entries,
+                Err(e) => {
+                    println!("[歷史紀錄] ⚠️ 無法解析 {}: {:?}", HISTORY_FILE, e);
+                    backup(path.as_deref(), "corrupt");
+                    Vec::new()
+                }
+            },
+            _ => Vec::new(),
+        };
+
+        println!("[歷史紀錄] 已載入 {} 筆紀錄", entries.len());
+
+        HistoryStore {
+            path,
+            entries,
+            current: None,
+        }
+    }
+
+    /// 對應 `PlayerEvent::Playing`，同一首歌從暫停恢復時只會繼續計時
+    pub fn on_playing(&mut self, kind: ItemKind, track_id: &str, title: &str, artists: &[String]) {
+        if let Some(current) = self.current.as_mut() {
+            if current.entry.track_id == track_id {
+                if current.resumed_at.is_none() {
+                    current.resumed_at = Some(Instant::now());
+                }
+                return;
+            }
+        }
+
+        self.finish_current();
+
+        self.current = Some(CurrentTrack {
+            entry: HistoryEntry {
+                track_id: track_id.to_string(),
+                title: title.to_string(),
+                artists: artists.to_vec(),
+                started_at: Utc::now(),
+                listened_ms: 0,
+                kind,
+            },
+            resumed_at: Some(Instant::now()),
+        });
+    }
+
+    /// 對應 `PlayerEvent::Paused`
+    pub fn on_paused(&mut self) {
+        if let Some(current) = self.current.as_mut() {
+            current.pause();
+        }
+    }
+
+    /// 對應 `PlayerEvent::EndOfTrack` / `Stopped`，把目前曲目寫入歷史紀錄
+    pub fn finish_current(&mut self) {
+        let Some(mut current) = self.current.take() else {
+            return;
+        };
+        current.pause();
+
+        self.entries.push(current.entry);
+        if self.entries.len() > MAX_ENTRIES {
+            let overflow = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..overflow);
+        }
+
+        if let Err(e) = self.save() {
+            println!("[歷史紀錄] ⚠️ 無法儲存歷史紀錄: {:?}", e);
+        }
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// 最近的 `n` 筆紀錄，最新的在前
+    pub fn recent(&self, n: usize) -> Vec<&HistoryEntry> {
+        self.entries.iter().rev().take(n).collect()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.entries)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("track_id,kind,title,artists,started_at,listened_ms,url\n");
+        for entry in &self.entries {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                entry.track_id,
+                entry.kind.as_str(),
+                csv_escape(&entry.title),
+                csv_escape(&entry.artists.join("; ")),
+                entry.started_at.to_rfc3339(),
+                entry.listened_ms,
+                entry.spotify_url(),
+            ));
+        }
+        csv
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        let file = HistoryFile {
+            entries: self.entries.clone(),
+        };
+        let content = serde_json::to_string(&file)?;
+
+        // 先寫入暫存檔再改名，避免寫到一半時損壞原檔案
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(tmp_path, path)
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// 組出 /history 的訊息，只放入不會超過 Discord 字數上限的行數
+fn history_message(lang: Language, mut lines: Vec<String>) -> String {
+    let mut len = tr!(lang, "history-title", count = lines.len()).chars().count();
+    let fits = lines
+        .iter()
+        .take_while(|line| {
+            len += 1 + line.chars().count();
+            len <= MESSAGE_LIMIT
+        })
+        .count();
+    lines.truncate(fits);
+
+    format!("{}\n{}", tr!(lang, "history-title", count = lines.len()), lines.join("\n"))
+}
+
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[derive(poise::ChoiceParameter)]
+pub enum ExportFormat {
+    #[name = "CSV"]
+    Csv,
+    #[name = "JSON"]
+    Json,
+}
+
+/// 顯示最近播放的曲目
+#[poise::command(slash_command, prefix_command)]
+pub async fn history(
+    ctx: PoiseContext<'_>,
+    #[description = "要顯示的筆數（預設 10，最多 25）"] n: Option<usize>,
+) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let n = n.unwrap_or(10).clamp(1, 25);
+    let store = ctx.data().history.lock().await;
+    let entries = store.recent(n);
+
+    if entries.is_empty() {
+        ctx.say(tr!(lang, "history-empty")).await?;
+        return Ok(());
+    }
+
+    let mut lines = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        lines.push(format!(
+            "`{:>2}.` {} [{} - {}](<{}>) · <t:{}:R> · {}",
+            i + 1,
+            entry.kind.emoji(),
+            entry.artists.join(", "),
+            entry.title,
+            entry.spotify_url(),
+            entry.started_at.timestamp(),
+            tr!(lang, "history-listened", duration = format_duration(entry.listened_ms)),
+        ));
+    }
+    drop(store);
+
+    ctx.say(history_message(lang, lines)).await?;
+    Ok(())
+}
+
+/// 匯出全部播放紀錄
+#[poise::command(slash_command, prefix_command)]
+pub async fn history_export(
+    ctx: PoiseContext<'_>,
+    #[description = "匯出格式（預設 CSV）"] format: Option<ExportFormat>,
+) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let (content, filename) = {
+        let store = ctx.data().history.lock().await;
+        match format.unwrap_or(ExportFormat::Csv) {
+            ExportFormat::Csv => (store.to_csv(), "history.csv"),
+            ExportFormat::Json => (store.to_json()?, "history.json"),
+        }
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(tr!(lang, "history-export-done"))
+            .attachment(serenity::all::CreateAttachment::bytes(content, filename)),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, artists: &[&str]) -> HistoryEntry {
+        HistoryEntry {
+            track_id: "3eR23VReFzcdmS7TYCrhCe".to_string(),
+            title: title.to_string(),
+            artists: artists.iter().map(|a| a.to_string()).collect(),
+            started_at: DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z").unwrap().with_timezone(&Utc),
+            listened_ms: 1234,
+            kind: ItemKind::Track,
+        }
+    }
+
+    #[test]
+    fn load_backs_up_a_corrupt_file() {
+        let dir = std::env::temp_dir().join(format!("history-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(HISTORY_FILE), "{ not json").unwrap();
+
+        let store = HistoryStore::load(dir.to_str());
+        let backup = std::fs::read_to_string(dir.join("history.json.corrupt.bak"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(store.entries().is_empty());
+        assert_eq!(backup.unwrap(), "{ not json");
+    }
+
+    #[test]
+    fn csv_escape_quotes_only_when_needed() {
+        assert_eq!(csv_escape("紅蓮の弓矢"), "紅蓮の弓矢");
+        assert_eq!(csv_escape("Hello, World"), "\"Hello, World\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_escape("line\r\nbreak"), "\"line\r\nbreak\"");
+        assert_eq!(csv_escape(""), "");
+    }
+
+    #[test]
+    fn history_message_stays_within_the_discord_limit() {
+        let lines: Vec<String> = (0..25).map(|i| format!("`{:>2}.` {}", i + 1, "歌".repeat(120))).collect();
+        let message = history_message(Language::En, lines);
+
+        assert!(message.chars().count() <= MESSAGE_LIMIT);
+        let shown = message.lines().count() - 1;
+        assert!(shown > 0 && shown < 25);
+        assert!(message.starts_with(&tr!(Language::En, "history-title", count = shown)));
+    }
+
+    #[test]
+    fn history_message_keeps_short_lists_whole() {
+        let lines: Vec<String> = (0..3).map(|i| format!("line {}", i)).collect();
+        let message = history_message(Language::En, lines);
+        assert_eq!(message.lines().count(), 4);
+    }
+
+    #[test]
+    fn to_csv_escapes_titles_and_artists() {
+        let store = HistoryStore {
+            path: None,
+            entries: vec![entry("Song, \"Live\"", &["A", "B, C"])],
+            current: None,
+        };
+        assert_eq!(
+            store.to_csv(),
+            "track_id,kind,title,artists,started_at,listened_ms,url\n\
+             3eR23VReFzcdmS7TYCrhCe,track,\"Song, \"\"Live\"\"\",\"A; B, C\",2024-01-02T03:04:05+00:00,1234,\
+             https://open.spotify.com/track/3eR23VReFzcdmS7TYCrhCe\n"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+///lib/i18n.rs
+use fluent::{FluentArgs, FluentResource};
+use lazy_static::lazy_static;
+use serenity::all::GuildId;
+
+use crate::lib::settings::Language;
+use crate::{Data, Error, PoiseContext};
+
+type FluentBundle = fluent::bundle::FluentBundle<FluentResource, intl_memoizer::concurrent::IntlLangMemoizer>;
+
+// 訊息目錄直接編進執行檔，部署時不需要另外附上 translations 目錄
+const ZH_TW_FTL: &str = include_str!("../../translations/zh-TW.ftl");
+const EN_US_FTL: &str = include_str!("../../translations/en-US.ftl");
+
+// Discord 用戶端的語系與對應的語言
+const DISCORD_LOCALES: [(&str, Language); 3] = [
+    ("zh-TW", Language::ZhTw),
+    ("en-US", Language::En),
+    ("en-GB", Language::En),
+];
+
+lazy_static! {
+    static ref TRANSLATIONS: Translations = Translations::load();
+}
+
+struct Translations {
+    zh_tw: FluentBundle,
+    en: FluentBundle,
+}
+
+impl Translations {
+    fn load() -> Translations {
+        Translations {
+            zh_tw: bundle("zh-TW", ZH_TW_FTL),
+            en: bundle("en-US", EN_US_FTL),
+        }
+    }
+
+    fn bundle(&self, language: Language) -> &FluentBundle {
+        match language {
+            Language::ZhTw => &self.zh_tw,
+            Language::En => &self.en,
+        }
+    }
+}
+
+fn bundle(locale: &str, source: &str) -> FluentBundle {
+    // 有語法錯誤時仍然使用能解析的部分
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+        println!("[語言] ⚠️ {}.ftl 有 {} 個語法錯誤: {:?}", locale, errors.len(), errors);
+        resource
+    });
+
+    let language_id = locale.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![language_id]);
+    // 不在參數前後插入 Unicode 隔離字元，否則 Discord 的提及與連結會失效
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        println!("[語言] ⚠️ 無法載入 {}.ftl: {:?}", locale, errors);
+    }
+    bundle
+}
+
+fn format_pattern(bundle: &FluentBundle, id: &str, attr: Option<&str>, args: Option<&FluentArgs>) -> Option<String> {
+    let message = bundle.get_message(id)?;
+    let pattern = match attr {
+        Some(attr) => message.get_attribute(attr)?.value(),
+        None => message.value()?,
+    };
+    Some(bundle.format_pattern(pattern, args, &mut vec![]).into_owned())
+}
+
+/// 取得訊息，找不到時依序改用繁體中文與訊息 ID
+pub fn format(language: Language, id: &str, args: Option<&FluentArgs>) -> String {
+    format_pattern(TRANSLATIONS.bundle(language), id, None, args)
+        .or_else(|| format_pattern(TRANSLATIONS.bundle(Language::ZhTw), id, None, args))
+        .unwrap_or_else(|| {
+            println!("[語言] ⚠️ 找不到訊息 `{}`", id);
+            id.to_string()
+        })
+}
+
+/// 取得翻譯，可以帶入參數：`tr!(lang, "history-empty")`、`tr!(lang, "history-title", count = 3)`
+macro_rules! tr {
+    ($language:expr, $id:expr $(, $name:ident = $value:expr)* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut args = fluent::FluentArgs::new();
+        $( args.set(stringify!($name), $value); )*
+        $crate::lib::i18n::format($language, $id, Some(&args))
+    }};
+}
+pub(crate) use tr;
+
+fn from_locale(locale: &str) -> Option<Language> {
+    if locale.starts_with("zh") {
+        Some(Language::ZhTw)
+    } else if locale.starts_with("en") {
+        Some(Language::En)
+    } else {
+        None
+    }
+}
+
+/// 伺服器設定的語言，沒有設定時使用 `Config` 的預設語言
+pub async fn guild_language(data: &Data, guild_id: Option<GuildId>) -> Language {
+    let configured = match guild_id {
+        Some(guild_id) => data.settings.lock().await.get(guild_id).language,
+        None => None,
+    };
+    configured.unwrap_or(data.config.language)
+}
+
+/// 回覆指令時使用的語言：伺服器設定優先，其次是使用者 Discord 用戶端的語系
+pub async fn language(ctx: PoiseContext<'_>) -> Language {
+    let configured = match ctx.guild_id() {
+        Some(guild_id) => ctx.data().settings.lock().await.get(guild_id).language,
+        None => None,
+    };
+    configured
+        .or_else(|| ctx.locale().and_then(from_locale))
+        .unwrap_or(ctx.data().config.language)
+}
+
+/// 依照訊息目錄設定斜線指令與參數的名稱和說明，`default` 是其他語系看到的語言
+///
+/// 指令的訊息 ID 為完整名稱（子指令以 `-` 連接），例如 `queue-add`：
+/// 訊息本身是指令名稱，`.description` 是說明，`.<參數>` 與 `.<參數>-description` 是參數的名稱與說明
+pub fn apply_translations(commands: &mut [poise::Command<Data, Error>], default: Language) {
+    translate_commands(commands, None, default);
+}
+
+// `qualified_name` 要等 `poise::Framework::new` 才會設定，所以這裡自己從上層指令組出 ID
+fn translate_commands(commands: &mut [poise::Command<Data, Error>], parent: Option<&str>, default: Language) {
+    for command in commands {
+        let id = match parent {
+            Some(parent) => format!("{}-{}", parent, command.name),
+            None => command.name.clone(),
+        };
+
+        for (locale, language) in DISCORD_LOCALES {
+            let bundle = TRANSLATIONS.bundle(language);
+            if let Some(name) = format_pattern(bundle, &id, None, None) {
+                command.name_localizations.insert(locale.to_string(), name);
+            }
+            if let Some(description) = format_pattern(bundle, &id, Some("description"), None) {
+                command.description_localizations.insert(locale.to_string(), description);
+            }
+            for parameter in &mut command.parameters {
+                if let Some(name) = format_pattern(bundle, &id, Some(&parameter.name), None) {
+                    parameter.name_localizations.insert(locale.to_string(), name);
+                }
+                let attr = format!("{}-description", parameter.name);
+                if let Some(description) = format_pattern(bundle, &id, Some(&attr), None) {
+                    parameter.description_localizations.insert(locale.to_string(), description);
+                }
+            }
+        }
+
+        // 其他語系的使用者看到預設語言的說明（名稱不變，避免指令被重新命名）
+        let bundle = TRANSLATIONS.bundle(default);
+        if let Some(description) = format_pattern(bundle, &id, Some("description"), None) {
+            command.description = Some(description);
+        }
+        for parameter in &mut command.parameters {
+            let attr = format!("{}-description", parameter.name);
+            if let Some(description) = format_pattern(bundle, &id, Some(&attr), None) {
+                parameter.description = Some(description);
+            }
+        }
+
+        translate_commands(&mut command.subcommands, Some(&id), default);
+    }
+}
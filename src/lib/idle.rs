@@ -0,0 +1,475 @@
+///lib/idle.rs
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use serenity::all::{ChannelId, Context, GuildId, UserId};
+
+use crate::lib::connect::PlaybackError;
+use crate::lib::i18n::tr;
+use crate::lib::settings::Language;
+use crate::Data;
+
+// 檢查閒置狀態的間隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 定時檢查一個伺服器後要做的事
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum IdleAction {
+    Nothing,
+    Warn { remaining: Duration },
+    Leave { idle_for: Duration },
+}
+
+/// 機器人所在頻道的聽眾變動後要做的事
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ListenerAction {
+    Nothing,
+    Pause,
+    Resume,
+    // 有人回來了，但設定為不自動繼續播放
+    StayPaused,
+}
+
+/// 播放與語音頻道的閒置狀態，由 Spotify 事件與定時檢查更新
+pub struct IdleState {
+    // 最後一次停止播放的時間，播放中為 None
+    paused_since: Option<Instant>,
+    // 各伺服器的語音頻道中只剩機器人的開始時間
+    alone_since: HashMap<GuildId, Instant>,
+    // 已經發出離開警告的伺服器
+    warned: HashSet<GuildId>,
+    // 因為頻道中沒有人而自動暫停的伺服器
+    auto_paused: HashSet<GuildId>,
+}
+
+impl IdleState {
+    pub fn new() -> IdleState {
+        IdleState {
+            paused_since: Some(Instant::now()),
+            alone_since: HashMap::new(),
+            warned: HashSet::new(),
+            auto_paused: HashSet::new(),
+        }
+    }
+
+    /// 對應 `PlayerEvent::Playing`
+    pub fn on_playing(&mut self) {
+        self.paused_since = None;
+        // 有人手動恢復播放時就不再自動繼續
+        self.auto_paused.clear();
+    }
+
+    /// 對應 `Paused` / `Stopped` / `SessionDisconnected`，重複呼叫不會重設計時
+    pub fn on_idle(&mut self) {
+        if self.paused_since.is_none() {
+            self.paused_since = Some(Instant::now());
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.paused_since.is_none()
+    }
+
+    fn paused_for(&self, now: Instant) -> Option<Duration> {
+        self.paused_since.map(|since| now.saturating_duration_since(since))
+    }
+
+    // 暫停或只剩機器人的時間較長者達到 `timeout` 時離開，剩下 `warning` 時警告一次
+    fn check_guild(
+        &mut self,
+        guild_id: GuildId,
+        alone: bool,
+        timeout: Duration,
+        warning: Duration,
+        now: Instant,
+    ) -> IdleAction {
+        let alone_for = if alone {
+            Some(now.saturating_duration_since(*self.alone_since.entry(guild_id).or_insert(now)))
+        } else {
+            self.alone_since.remove(&guild_id);
+            None
+        };
+
+        let Some(idle_for) = self.paused_for(now).max(alone_for) else {
+            self.warned.remove(&guild_id);
+            return IdleAction::Nothing;
+        };
+
+        if idle_for >= timeout {
+            IdleAction::Leave { idle_for }
+        } else if idle_for + warning >= timeout && self.warned.insert(guild_id) {
+            IdleAction::Warn {
+                remaining: timeout - idle_for,
+            }
+        } else {
+            IdleAction::Nothing
+        }
+    }
+
+    // 沒有人在聽時暫停一次，之後有人回來才依照設定繼續播放
+    fn update_listeners(&mut self, guild_id: GuildId, has_listeners: bool, resume_on_return: bool) -> ListenerAction {
+        if !has_listeners {
+            if self.is_playing() && self.auto_paused.insert(guild_id) {
+                ListenerAction::Pause
+            } else {
+                ListenerAction::Nothing
+            }
+        } else if self.auto_paused.remove(&guild_id) {
+            if resume_on_return {
+                ListenerAction::Resume
+            } else {
+                ListenerAction::StayPaused
+            }
+        } else {
+            ListenerAction::Nothing
+        }
+    }
+
+    // 停止播放超過 `timeout` 時回傳已停止的時間，0 代表不會關閉
+    fn connect_timed_out(&self, timeout: Duration, now: Instant) -> Option<Duration> {
+        if timeout.is_zero() {
+            return None;
+        }
+        self.paused_for(now).filter(|paused_for| *paused_for >= timeout)
+    }
+
+    fn forget_guild(&mut self, guild_id: GuildId) {
+        self.alone_since.remove(&guild_id);
+        self.warned.remove(&guild_id);
+        self.auto_paused.remove(&guild_id);
+    }
+}
+
+impl Default for IdleState {
+    fn default() -> Self {
+        IdleState::new()
+    }
+}
+
+/// 語音頻道中除了機器人以外的使用者
+pub fn human_listeners(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> Vec<UserId> {
+    let Some(guild) = ctx.cache.guild(guild_id) else {
+        return Vec::new();
+    };
+
+    guild
+        .voice_states
+        .values()
+        .filter(|state| state.channel_id == Some(channel_id))
+        .filter(|state| {
+            let is_bot = match state.member.as_ref() {
+                Some(member) => member.user.bot,
+                None => ctx.cache.user(state.user_id).is_some_and(|user| user.bot),
+            };
+            !is_bot
+        })
+        .map(|state| state.user_id)
+        .collect()
+}
+
+/// 機器人所在頻道的成員變動時呼叫：沒有人在聽就暫停，有人回來時依設定繼續播放
+pub async fn update_listeners(ctx: &Context, data: &Data, guild_id: GuildId) {
+    let Some(manager) = songbird::get(ctx).await else {
+        return;
+    };
+    let Some(call) = manager.get(guild_id) else {
+        return;
+    };
+    let Some(channel) = call.lock().await.current_channel() else {
+        return;
+    };
+
+    let has_listeners = !human_listeners(ctx, guild_id, ChannelId::new(channel.0.get())).is_empty();
+    let action = data
+        .idle
+        .lock()
+        .await
+        .update_listeners(guild_id, has_listeners, data.config.resume_on_return);
+
+    match action {
+        ListenerAction::Nothing => {}
+        ListenerAction::Pause => {
+            println!("[閒置] ⏸️ 頻道中沒有其他人，暫停播放 (Guild: {:?})", guild_id);
+            if let Err(PlaybackError::Spirc(e)) = data.connect.pause().await {
+                println!("[閒置] ⚠️ 暫停失敗: {:?}", e);
+            }
+        }
+        ListenerAction::StayPaused => {
+            println!("[閒置] ℹ️ 有人回到頻道，保持暫停 (Guild: {:?})", guild_id);
+        }
+        ListenerAction::Resume => {
+            println!("[閒置] ▶️ 有人回到頻道，繼續播放 (Guild: {:?})", guild_id);
+            if let Err(PlaybackError::Spirc(e)) = data.connect.play().await {
+                println!("[閒置] ⚠️ 繼續播放失敗: {:?}", e);
+            }
+        }
+    }
+}
+
+fn format_minutes(lang: Language, duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 {
+        tr!(lang, "duration-minutes", minutes = secs / 60)
+    } else {
+        tr!(lang, "duration-seconds", seconds = secs)
+    }
+}
+
+/// 啟動背景檢查，依照設定離開語音頻道並關閉 Spotify Connect
+pub fn spawn_idle_monitor(ctx: Context, data: Data) -> tokio::task::JoinHandle<()> {
+    println!(
+        "[閒置] 監控已啟動（離開語音: {} 分鐘, 關閉 Connect: {} 分鐘）",
+        data.config.idle_timeout_minutes, data.config.connect_idle_timeout_minutes
+    );
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            check_voice(&ctx, &data).await;
+            check_connect(&ctx, &data).await;
+        }
+    })
+}
+
+// 暫停或只剩機器人超過時間時離開語音頻道，離開前先在頻道中警告
+async fn check_voice(ctx: &Context, data: &Data) {
+    let Some(manager) = songbird::get(ctx).await else {
+        return;
+    };
+
+    for guild_id in ctx.cache.guilds() {
+        let Some(call) = manager.get(guild_id) else {
+            data.idle.lock().await.forget_guild(guild_id);
+            continue;
+        };
+
+        // 伺服器沒有另外設定時使用全域設定
+        let settings = data.settings.lock().await.get(guild_id);
+        let timeout_minutes = settings.idle_timeout_minutes.unwrap_or(data.config.idle_timeout_minutes);
+        let timeout = Duration::from_secs(timeout_minutes * 60);
+        if timeout.is_zero() {
+            continue;
+        }
+        let warning_seconds = settings.idle_warning_seconds.unwrap_or(data.config.idle_warning_seconds);
+        let warning = Duration::from_secs(warning_seconds).min(timeout);
+        let lang = settings.language.unwrap_or(data.config.language);
+
+        let Some(channel) = call.lock().await.current_channel() else {
+            continue;
+        };
+        let channel_id = ChannelId::new(channel.0.get());
+        let alone = human_listeners(ctx, guild_id, channel_id).is_empty();
+
+        let action = data
+            .idle
+            .lock()
+            .await
+            .check_guild(guild_id, alone, timeout, warning, Instant::now());
+
+        match action {
+            IdleAction::Nothing => {}
+            IdleAction::Leave { idle_for } => {
+                println!(
+                    "[閒置] 👋 已閒置 {}，離開語音頻道 (Guild: {:?})",
+                    format_minutes(Language::ZhTw, idle_for),
+                    guild_id
+                );
+                let _ = channel_id
+                    .say(&ctx.http, tr!(lang, "idle-left", duration = format_minutes(lang, idle_for)))
+                    .await;
+
+                // 只剩機器人時可能仍在播放，先暫停避免 Spotify 繼續計算播放
+                if alone {
+                    let _ = data.connect.pause().await;
+                }
+
+                crate::lib::voice::leave(ctx, data, guild_id).await;
+                data.idle.lock().await.forget_guild(guild_id);
+            }
+            IdleAction::Warn { remaining } => {
+                let reason = if alone { "idle-reason-alone" } else { "idle-reason-paused" };
+                println!(
+                    "[閒置] ⚠️ {}，{} 後離開語音頻道 (Guild: {:?})",
+                    tr!(Language::ZhTw, reason),
+                    format_minutes(Language::ZhTw, remaining),
+                    guild_id
+                );
+                let _ = channel_id
+                    .say(
+                        &ctx.http,
+                        tr!(
+                            lang,
+                            "idle-warning",
+                            reason = tr!(lang, reason),
+                            duration = format_minutes(lang, remaining)
+                        ),
+                    )
+                    .await;
+            }
+        }
+    }
+}
+
+// 不在任何語音頻道且停止播放超過時間時關閉 Spotify Connect
+async fn check_connect(ctx: &Context, data: &Data) {
+    let timeout = Duration::from_secs(data.config.connect_idle_timeout_minutes * 60);
+    if timeout.is_zero() {
+        return;
+    }
+
+    let Some(paused_for) = data.idle.lock().await.connect_timed_out(timeout, Instant::now()) else {
+        return;
+    };
+
+    if let Some(manager) = songbird::get(ctx).await {
+        if ctx.cache.guilds().iter().any(|guild_id| manager.get(*guild_id).is_some()) {
+            return;
+        }
+    }
+
+    if !data.connect.state().is_enabled() {
+        return;
+    }
+
+    println!("[閒置] 🔌 已閒置 {}，關閉 Spotify Connect（使用者重新加入語音頻道時會再啟用）", format_minutes(Language::ZhTw, paused_for));
+    data.connect.disable().await;
+
+    *data.now_playing.lock().await = None;
+    ctx.set_presence(None, serenity::all::OnlineStatus::Online);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD: GuildId = GuildId::new(1);
+    const TIMEOUT: Duration = Duration::from_secs(10 * 60);
+    const WARNING: Duration = Duration::from_secs(60);
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn playing() -> IdleState {
+        let mut idle = IdleState::new();
+        idle.on_playing();
+        idle
+    }
+
+    #[test]
+    fn paused_warns_once_then_leaves() {
+        let start = Instant::now();
+        let mut idle = playing();
+        idle.paused_since = Some(start);
+
+        assert_eq!(idle.check_guild(GUILD, false, TIMEOUT, WARNING, start + secs(480)), IdleAction::Nothing);
+        assert_eq!(
+            idle.check_guild(GUILD, false, TIMEOUT, WARNING, start + secs(545)),
+            IdleAction::Warn { remaining: secs(55) }
+        );
+        assert_eq!(idle.check_guild(GUILD, false, TIMEOUT, WARNING, start + secs(570)), IdleAction::Nothing);
+        assert_eq!(
+            idle.check_guild(GUILD, false, TIMEOUT, WARNING, start + secs(600)),
+            IdleAction::Leave { idle_for: secs(600) }
+        );
+    }
+
+    #[test]
+    fn playing_alone_counts_from_when_everyone_left() {
+        let start = Instant::now();
+        let mut idle = playing();
+
+        assert_eq!(idle.check_guild(GUILD, true, TIMEOUT, WARNING, start), IdleAction::Nothing);
+        assert_eq!(
+            idle.check_guild(GUILD, true, TIMEOUT, WARNING, start + secs(570)),
+            IdleAction::Warn { remaining: secs(30) }
+        );
+        assert_eq!(
+            idle.check_guild(GUILD, true, TIMEOUT, WARNING, start + secs(600)),
+            IdleAction::Leave { idle_for: secs(600) }
+        );
+    }
+
+    #[test]
+    fn rejoining_resets_the_timer_and_the_warning() {
+        let start = Instant::now();
+        let mut idle = playing();
+
+        idle.check_guild(GUILD, true, TIMEOUT, WARNING, start);
+        assert!(matches!(
+            idle.check_guild(GUILD, true, TIMEOUT, WARNING, start + secs(550)),
+            IdleAction::Warn { .. }
+        ));
+
+        // 有人回來後不再閒置，之後又只剩機器人時重新計時並再次警告
+        assert_eq!(idle.check_guild(GUILD, false, TIMEOUT, WARNING, start + secs(560)), IdleAction::Nothing);
+        assert_eq!(idle.check_guild(GUILD, true, TIMEOUT, WARNING, start + secs(600)), IdleAction::Nothing);
+        assert_eq!(
+            idle.check_guild(GUILD, true, TIMEOUT, WARNING, start + secs(1150)),
+            IdleAction::Warn { remaining: secs(50) }
+        );
+    }
+
+    #[test]
+    fn resuming_playback_clears_the_warning() {
+        let start = Instant::now();
+        let mut idle = playing();
+        idle.paused_since = Some(start);
+        assert!(matches!(
+            idle.check_guild(GUILD, false, TIMEOUT, WARNING, start + secs(550)),
+            IdleAction::Warn { .. }
+        ));
+
+        idle.on_playing();
+        assert_eq!(idle.check_guild(GUILD, false, TIMEOUT, WARNING, start + secs(700)), IdleAction::Nothing);
+        assert!(!idle.warned.contains(&GUILD));
+    }
+
+    #[test]
+    fn listeners_leaving_pauses_once_and_rejoining_resumes() {
+        let mut idle = playing();
+
+        assert_eq!(idle.update_listeners(GUILD, false, true), ListenerAction::Pause);
+        // 暫停事件送達後狀態變成閒置，再次檢查不會重複暫停
+        idle.on_idle();
+        assert_eq!(idle.update_listeners(GUILD, false, true), ListenerAction::Nothing);
+
+        assert_eq!(idle.update_listeners(GUILD, true, true), ListenerAction::Resume);
+        assert_eq!(idle.update_listeners(GUILD, true, true), ListenerAction::Nothing);
+    }
+
+    #[test]
+    fn rejoining_stays_paused_when_resume_is_off() {
+        let mut idle = playing();
+
+        assert_eq!(idle.update_listeners(GUILD, false, false), ListenerAction::Pause);
+        assert_eq!(idle.update_listeners(GUILD, true, false), ListenerAction::StayPaused);
+    }
+
+    #[test]
+    fn manual_pause_or_resume_is_never_overridden() {
+        // 使用者自己暫停時，離開頻道不會記成自動暫停
+        let mut idle = IdleState::new();
+        assert_eq!(idle.update_listeners(GUILD, false, true), ListenerAction::Nothing);
+        assert_eq!(idle.update_listeners(GUILD, true, true), ListenerAction::Nothing);
+
+        // 自動暫停後有人手動繼續播放，之後回來的人不會再觸發繼續播放
+        let mut idle = playing();
+        idle.update_listeners(GUILD, false, true);
+        idle.on_playing();
+        assert_eq!(idle.update_listeners(GUILD, true, true), ListenerAction::Nothing);
+    }
+
+    #[test]
+    fn connect_times_out_only_after_the_configured_pause() {
+        let start = Instant::now();
+        let mut idle = playing();
+        assert_eq!(idle.connect_timed_out(TIMEOUT, start + secs(3600)), None);
+
+        idle.paused_since = Some(start);
+        assert_eq!(idle.connect_timed_out(TIMEOUT, start + secs(599)), None);
+        assert_eq!(idle.connect_timed_out(TIMEOUT, start + secs(600)), Some(secs(600)));
+        assert_eq!(idle.connect_timed_out(Duration::ZERO, start + secs(3600)), None);
+    }
+}
@@ -0,0 +1,469 @@
+///lib/metadata.rs
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use librespot::core::spotify_id::{SpotifyId, SpotifyItemType};
+use librespot::core::{session::Session, Error as LibrespotError};
+use librespot::metadata::{Album, Artist, Episode, Metadata, Track};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::lib::storage::backup;
+
+const METADATA_FILE: &str = "metadata_cache.json";
+// 每種 Metadata 在記憶體中最多保留的筆數
+const CAPACITY: usize = 2_000;
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrackMeta {
+    pub name: String,
+    // base62 ID
+    pub artist_ids: Vec<String>,
+    pub album_id: String,
+    pub album_name: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ArtistMeta {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AlbumMeta {
+    pub name: String,
+    // base62 ID
+    pub track_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EpisodeMeta {
+    pub name: String,
+    pub show_name: String,
+    pub duration_ms: u64,
+    pub is_audiobook_chapter: bool,
+}
+
+/// 正在播放的項目種類
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    #[default]
+    Track,
+    Episode,
+    Audiobook,
+}
+
+impl ItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemKind::Track => "track",
+            ItemKind::Episode => "episode",
+            ItemKind::Audiobook => "audiobook",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            ItemKind::Track => "🎵",
+            ItemKind::Episode => "🎙️",
+            ItemKind::Audiobook => "📖",
+        }
+    }
+
+    // open.spotify.com 網址中的路徑，有聲書章節也是以 episode 播放
+    pub fn url_path(&self) -> &'static str {
+        match self {
+            ItemKind::Track => "track",
+            ItemKind::Episode | ItemKind::Audiobook => "episode",
+        }
+    }
+}
+
+/// 曲目或 Podcast 單集的共通資訊，供狀態、公告與播放紀錄使用
+#[derive(Clone, Debug)]
+pub struct PlayableItem {
+    pub kind: ItemKind,
+    // base62 ID
+    pub id: String,
+    pub title: String,
+    // 曲目的演出者，或單集所屬的節目
+    pub artists: Vec<String>,
+    pub album: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl PlayableItem {
+    pub fn url(&self) -> String {
+        format!("https://open.spotify.com/{}/{}", self.kind.url_path(), self.id)
+    }
+}
+
+pub fn spotify_id(kind: &str, base62: &str) -> Option<SpotifyId> {
+    SpotifyId::from_uri(&format!("spotify:{}:{}", kind, base62)).ok()
+}
+
+// 以最後使用時間淘汰的快取，每個 key 用 OnceCell 讓同時的請求只抓一次
+struct LruMap<V> {
+    entries: HashMap<String, (Arc<OnceCell<V>>, u64)>,
+    clock: AtomicU64,
+}
+
+impl<V: Clone> LruMap<V> {
+    fn new(initial: HashMap<String, V>) -> LruMap<V> {
+        let entries = initial
+            .into_iter()
+            .map(|(key, value)| (key, (Arc::new(OnceCell::new_with(Some(value))), 0)))
+            .collect();
+        LruMap {
+            entries,
+            clock: AtomicU64::new(1),
+        }
+    }
+
+    fn cell(&mut self, key: &str) -> Arc<OnceCell<V>> {
+        let tick = self.clock.fetch_add(1, Ordering::Relaxed);
+        if let Some((cell, last_used)) = self.entries.get_mut(key) {
+            *last_used = tick;
+            return cell.clone();
+        }
+
+        if self.entries.len() >= CAPACITY {
+            // 淘汰最久沒用到的項目
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone())
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        let cell = Arc::new(OnceCell::new());
+        self.entries.insert(key.to_string(), (cell.clone(), tick));
+        cell
+    }
+
+    fn snapshot(&self) -> HashMap<String, V> {
+        self.entries
+            .iter()
+            .filter_map(|(key, (cell, _))| cell.get().map(|value| (key.clone(), value.clone())))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct MetadataFile {
+    tracks: HashMap<String, TrackMeta>,
+    artists: HashMap<String, ArtistMeta>,
+    albums: HashMap<String, AlbumMeta>,
+    #[serde(default)]
+    episodes: HashMap<String, EpisodeMeta>,
+}
+
+/// 共用的 Metadata 快取：同一個 ID 同時只會發出一次請求，結果保存在 `cache_dir`
+pub struct MetadataCache {
+    path: Option<PathBuf>,
+    tracks: Mutex<LruMap<TrackMeta>>,
+    artists: Mutex<LruMap<ArtistMeta>>,
+    albums: Mutex<LruMap<AlbumMeta>>,
+    episodes: Mutex<LruMap<EpisodeMeta>>,
+    // 同一時間只讓一個寫入進行，避免同時抓取時互相覆寫暫存檔
+    save_lock: Mutex<()>,
+}
+
+impl MetadataCache {
+    /// 從 `cache_dir` 載入快取，沒有快取目錄時只保存在記憶體中
+    pub fn load(cache_dir: Option<&str>) -> MetadataCache {
+        let path = cache_dir.map(|dir| Path::new(dir).join(METADATA_FILE));
+
+        let file = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => match serde_json::from_str::<MetadataFile>(&content) {
+                Ok(file) => file,
+                Err(e) => {
+                    println!("[Metadata] ⚠️ 無法解析 {}: {:?}", METADATA_FILE, e);
+                    backup(path.as_deref(), "corrupt");
+                    MetadataFile::default()
+                }
+            },
+            _ => MetadataFile::default(),
+        };
+
+        println!(
+            "[Metadata] 已載入 {} 首曲目、{} 位演出者、{} 張專輯、{} 集節目",
+            file.tracks.len(),
+            file.artists.len(),
+            file.albums.len(),
+            file.episodes.len()
+        );
+
+        MetadataCache {
+            path,
+            tracks: Mutex::new(LruMap::new(file.tracks)),
+            artists: Mutex::new(LruMap::new(file.artists)),
+            albums: Mutex::new(LruMap::new(file.albums)),
+            episodes: Mutex::new(LruMap::new(file.episodes)),
+            save_lock: Mutex::new(()),
+        }
+    }
+
+    pub async fn track(&self, session: &Session, id: &SpotifyId) -> Result<TrackMeta, LibrespotError> {
+        self.get_or_fetch(&self.tracks, id, || async {
+            let track = Track::get(session, id).await?;
+            Ok(TrackMeta {
+                name: track.name.clone(),
+                artist_ids: track
+                    .artists
+                    .iter()
+                    .filter_map(|artist| artist.id.to_base62().ok())
+                    .collect(),
+                album_id: track.album.id.to_base62().unwrap_or_default(),
+                album_name: track.album.name.clone(),
+                duration_ms: track.duration.max(0) as u64,
+            })
+        })
+        .await
+    }
+
+    pub async fn artist(&self, session: &Session, id: &SpotifyId) -> Result<ArtistMeta, LibrespotError> {
+        self.get_or_fetch(&self.artists, id, || async {
+            let artist = Artist::get(session, id).await?;
+            Ok(ArtistMeta { name: artist.name })
+        })
+        .await
+    }
+
+    pub async fn album(&self, session: &Session, id: &SpotifyId) -> Result<AlbumMeta, LibrespotError> {
+        self.get_or_fetch(&self.albums, id, || async {
+            let album = Album::get(session, id).await?;
+            Ok(AlbumMeta {
+                name: album.name.clone(),
+                track_ids: album.tracks().filter_map(|id| id.to_base62().ok()).collect(),
+            })
+        })
+        .await
+    }
+
+    pub async fn episode(&self, session: &Session, id: &SpotifyId) -> Result<EpisodeMeta, LibrespotError> {
+        self.get_or_fetch(&self.episodes, id, || async {
+            let episode = Episode::get(session, id).await?;
+            Ok(EpisodeMeta {
+                name: episode.name.clone(),
+                show_name: episode.show_name.clone(),
+                duration_ms: episode.duration.max(0) as u64,
+                is_audiobook_chapter: episode.is_audiobook_chapter,
+            })
+        })
+        .await
+    }
+
+    /// 依照 `SpotifyId` 的種類查詢曲目或 Podcast / 有聲書單集
+    pub async fn playable(&self, session: &Session, id: &SpotifyId) -> Result<PlayableItem, LibrespotError> {
+        let base62 = id.to_base62()?;
+
+        if id.item_type == SpotifyItemType::Episode {
+            let episode = self.episode(session, id).await?;
+            let kind = if episode.is_audiobook_chapter {
+                ItemKind::Audiobook
+            } else {
+                ItemKind::Episode
+            };
+            return Ok(PlayableItem {
+                kind,
+                id: base62,
+                title: episode.name,
+                artists: vec![episode.show_name],
+                album: None,
+                duration_ms: episode.duration_ms,
+            });
+        }
+
+        let track = self.track(session, id).await?;
+        let artists = self.artist_names(session, &track).await;
+        Ok(PlayableItem {
+            kind: ItemKind::Track,
+            id: base62,
+            title: track.name,
+            artists,
+            album: Some(track.album_name),
+            duration_ms: track.duration_ms,
+        })
+    }
+
+    /// 同時查詢曲目的所有演出者名稱，查不到的會被略過
+    pub async fn artist_names(&self, session: &Session, track: &TrackMeta) -> Vec<String> {
+        let lookups = track.artist_ids.iter().map(|artist_id| async move {
+            let id = spotify_id("artist", artist_id)?;
+            self.artist(session, &id).await.ok().map(|artist| artist.name)
+        });
+        join_all(lookups).await.into_iter().flatten().collect()
+    }
+
+    async fn get_or_fetch<V, F, Fut>(
+        &self,
+        map: &Mutex<LruMap<V>>,
+        id: &SpotifyId,
+        fetch: F,
+    ) -> Result<V, LibrespotError>
+    where
+        V: Clone + Serialize + DeserializeOwned,
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<V, LibrespotError>>,
+    {
+        let key = id.to_base62()?;
+        // 只在取得 cell 時鎖住，網路請求期間不持有任何鎖
+        let cell = map.lock().await.cell(&key);
+
+        let mut fetched = false;
+        let value = cell
+            .get_or_try_init(|| async {
+                fetched = true;
+                let mut attempt = 1;
+                loop {
+                    match fetch().await {
+                        Ok(value) => return Ok(value),
+                        Err(e) if attempt < MAX_ATTEMPTS => {
+                            println!("[Metadata] ⚠️ 無法獲取 {} (嘗試 {}/{}): {:?}", key, attempt, MAX_ATTEMPTS, e);
+                            tokio::time::sleep(RETRY_DELAY * attempt).await;
+                            attempt += 1;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            })
+            .await?
+            .clone();
+
+        if fetched {
+            self.save().await;
+        }
+        Ok(value)
+    }
+
+    async fn save(&self) {
+        let Some(ref path) = self.path else {
+            return;
+        };
+
+        // 在寫入鎖內取快照，後寫入的檔案一定包含先前所有的結果
+        let _guard = self.save_lock.lock().await;
+        let file = MetadataFile {
+            tracks: self.tracks.lock().await.snapshot(),
+            artists: self.artists.lock().await.snapshot(),
+            albums: self.albums.lock().await.snapshot(),
+            episodes: self.episodes.lock().await.snapshot(),
+        };
+
+        let path = path.clone();
+        let result = tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let content = serde_json::to_string(&file)?;
+            // 先寫入暫存檔再改名，避免寫到一半時損壞原檔案
+            let tmp_path = path.with_extension("json.tmp");
+            std::fs::write(&tmp_path, content)?;
+            std::fs::rename(tmp_path, path)
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => println!("[Metadata] ⚠️ 無法儲存快取: {:?}", e),
+            Err(e) => println!("[Metadata] ⚠️ 儲存快取的工作失敗: {:?}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    fn artist(name: &str) -> Result<ArtistMeta, LibrespotError> {
+        Ok(ArtistMeta { name: name.to_string() })
+    }
+
+    #[test]
+    fn lru_map_evicts_the_least_recently_used_entry() {
+        let mut map = LruMap::<ArtistMeta>::new(HashMap::new());
+        for i in 0..CAPACITY {
+            map.cell(&i.to_string());
+        }
+        // 重新使用最早的項目，讓第二個變成最久沒用到的
+        map.cell("0");
+        map.cell("new");
+
+        assert_eq!(map.entries.len(), CAPACITY);
+        assert!(map.entries.contains_key("0"));
+        assert!(map.entries.contains_key("new"));
+        assert!(!map.entries.contains_key("1"));
+    }
+
+    #[test]
+    fn lru_map_snapshot_skips_unfinished_entries() {
+        let mut initial = HashMap::new();
+        initial.insert("loaded".to_string(), ArtistMeta { name: "A".to_string() });
+        let mut map = LruMap::new(initial);
+        map.cell("pending");
+
+        let snapshot = map.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot["loaded"].name, "A");
+    }
+
+    #[tokio::test]
+    async fn concurrent_fetches_of_the_same_id_share_one_request() {
+        let cache = MetadataCache::load(None);
+        let id = spotify_id("artist", "0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+        let calls = &AtomicU32::new(0);
+        let fetch = move || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            artist("Band of Horses")
+        };
+
+        let (first, second) = tokio::join!(
+            cache.get_or_fetch(&cache.artists, &id, fetch),
+            cache.get_or_fetch(&cache.artists, &id, fetch)
+        );
+        assert_eq!(first.unwrap().name, "Band of Horses");
+        assert_eq!(second.unwrap().name, "Band of Horses");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_fetches_all_end_up_in_the_saved_file() {
+        let dir = std::env::temp_dir().join(format!("metadata-cache-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache = MetadataCache::load(dir.to_str());
+
+        let ids = [
+            "0OdUWJ0sBjDrqHygGUXeCF",
+            "4Z8W4fKeB5YxbusRsdQVPb",
+            "3WrFJ7ztbogyGnTHbHJFl2",
+            "1dfeR4HaWDbWqFHLkxsg1d",
+        ];
+        let fetches = ids.iter().map(|base62| {
+            let cache = &cache;
+            async move {
+                let id = spotify_id("artist", base62).unwrap();
+                cache.get_or_fetch(&cache.artists, &id, move || async move { artist(base62) }).await
+            }
+        });
+        for result in join_all(fetches).await {
+            result.unwrap();
+        }
+
+        let reloaded = MetadataCache::load(dir.to_str());
+        let artists = reloaded.artists.lock().await.snapshot();
+        let tmp_left = dir.join("metadata_cache.json.tmp").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(artists.len(), ids.len());
+        assert!(!tmp_left);
+    }
+}
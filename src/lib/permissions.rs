@@ -0,0 +1,355 @@
+///lib/permissions.rs
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serenity::all::{Member, RoleId};
+
+use crate::lib::i18n::{self, tr};
+use crate::{Error, PoiseContext, say_ephemeral};
+
+/// 指令所需的能力，在指令上以 `custom_data = Capability::...` 標記
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, poise::ChoiceParameter)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    #[name = "control"]
+    Control,
+    #[name = "volume"]
+    Volume,
+    #[name = "queue"]
+    Queue,
+    #[name = "admin"]
+    Admin,
+    #[name = "quiz-admin"]
+    QuizAdmin,
+}
+
+impl Capability {
+    const ALL: [Capability; 5] = [
+        Capability::Control,
+        Capability::Volume,
+        Capability::Queue,
+        Capability::Admin,
+        Capability::QuizAdmin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Control => "control",
+            Capability::Volume => "volume",
+            Capability::Queue => "queue",
+            Capability::Admin => "admin",
+            Capability::QuizAdmin => "quiz-admin",
+        }
+    }
+
+    // 沒有設定身分組時，管理類的能力只開放給有「管理伺服器」權限的成員
+    fn open_by_default(&self) -> bool {
+        matches!(self, Capability::Control | Capability::Volume | Capability::Queue)
+    }
+}
+
+/// 影響所有伺服器的指令（例如共用的猜歌題庫）以 `custom_data = OwnerOnly` 標記，只開放給 `DISCORD_USER_ID` 的使用者
+pub struct OwnerOnly;
+
+// 指令以 `custom_data` 標記的權限需求
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Requirement {
+    Anyone,
+    Owner,
+    Capability(Capability),
+}
+
+// 在伺服器中呼叫指令的成員，私訊中沒有這些資訊
+struct GuildMember<'a> {
+    is_manager: bool,
+    roles: &'a [RoleId],
+    // 伺服器設定給這個能力的身分組
+    allowed_roles: &'a HashSet<RoleId>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Decision {
+    Allow,
+    DenyOwnerOnly,
+    DenyGuildOnly,
+    // 沒有設定身分組，只開放給有「管理伺服器」權限的成員
+    DenyManagersOnly,
+    DenyMissingRole,
+}
+
+// 權限判斷本身，不需要 Discord 的連線
+fn decide(requirement: Requirement, is_owner: bool, member: Option<GuildMember<'_>>) -> Decision {
+    // 設定的擁有者不受限制
+    if is_owner {
+        return Decision::Allow;
+    }
+    let capability = match requirement {
+        Requirement::Anyone => return Decision::Allow,
+        Requirement::Owner => return Decision::DenyOwnerOnly,
+        Requirement::Capability(capability) => capability,
+    };
+    // 私訊中沒有身分組可以判斷，管理類的指令只開放給擁有者
+    let Some(member) = member else {
+        return if capability.open_by_default() {
+            Decision::Allow
+        } else {
+            Decision::DenyGuildOnly
+        };
+    };
+    if member.is_manager {
+        return Decision::Allow;
+    }
+
+    if member.allowed_roles.is_empty() {
+        if capability.open_by_default() {
+            Decision::Allow
+        } else {
+            Decision::DenyManagersOnly
+        }
+    } else if member.roles.iter().any(|role| member.allowed_roles.contains(role)) {
+        Decision::Allow
+    } else {
+        Decision::DenyMissingRole
+    }
+}
+
+fn is_manager(ctx: PoiseContext<'_>, member: &Member) -> bool {
+    // 斜線指令的 Member 會附上權限，否則從快取計算
+    let permissions = member
+        .permissions
+        .or_else(|| ctx.guild().map(|guild| guild.member_permissions(member)));
+    permissions.is_some_and(|p| p.administrator() || p.manage_guild())
+}
+
+fn format_roles(roles: &HashSet<RoleId>) -> String {
+    roles.iter().map(|role| format!("<@&{}>", role)).collect::<Vec<_>>().join(" ")
+}
+
+/// 註冊為 poise 的 `command_check`，沒有權限時以僅自己可見的訊息說明原因
+pub async fn command_check(ctx: PoiseContext<'_>) -> Result<bool, Error> {
+    let custom_data = &ctx.command().custom_data;
+    let requirement = if custom_data.is::<OwnerOnly>() {
+        Requirement::Owner
+    } else {
+        match custom_data.downcast_ref::<Capability>() {
+            Some(capability) => Requirement::Capability(*capability),
+            None => Requirement::Anyone,
+        }
+    };
+    let is_owner = ctx.author().id.get() == ctx.data().config.discord_user_id;
+
+    // 只有需要判斷身分組時才查詢成員與伺服器設定
+    let mut member = None;
+    let mut allowed_roles = HashSet::new();
+    if let (Requirement::Capability(capability), Some(guild_id), false) = (requirement, ctx.guild_id(), is_owner) {
+        let Some(author) = ctx.author_member().await else {
+            return Ok(false);
+        };
+        allowed_roles = ctx.data().settings.lock().await.get(guild_id).roles(capability);
+        member = Some(author);
+    }
+    let guild_member = member.as_deref().map(|member| GuildMember {
+        is_manager: is_manager(ctx, member),
+        roles: &member.roles,
+        allowed_roles: &allowed_roles,
+    });
+
+    let decision = decide(requirement, is_owner, guild_member);
+    if decision == Decision::Allow {
+        return Ok(true);
+    }
+
+    let lang = i18n::language(ctx).await;
+    let capability = match requirement {
+        Requirement::Capability(capability) => capability.as_str(),
+        _ => "",
+    };
+    let reason = match decision {
+        Decision::Allow | Decision::DenyOwnerOnly => tr!(lang, "permission-owner-only"),
+        Decision::DenyGuildOnly => tr!(lang, "permission-guild-only"),
+        Decision::DenyManagersOnly => tr!(lang, "permission-denied-managers", capability = capability),
+        Decision::DenyMissingRole => tr!(
+            lang,
+            "permission-denied-roles",
+            capability = capability,
+            roles = format_roles(&allowed_roles)
+        ),
+    };
+    say_ephemeral(ctx, reason).await?;
+    Ok(false)
+}
+
+/// 管理身分組可以使用的指令
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("permissions_grant", "permissions_revoke", "permissions_list")
+)]
+pub async fn permissions(_ctx: PoiseContext<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 讓身分組擁有指定的能力
+#[poise::command(slash_command, guild_only, rename = "grant", custom_data = Capability::Admin)]
+pub async fn permissions_grant(
+    ctx: PoiseContext<'_>,
+    #[description = "能力"] capability: Capability,
+    #[description = "身分組"] role: serenity::all::Role,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let lang = i18n::language(ctx).await;
+
+    let added = ctx
+        .data()
+        .settings
+        .lock()
+        .await
+        .update(guild_id, |settings| settings.permissions.entry(capability).or_default().insert(role.id))?;
+    let message = if added {
+        tr!(lang, "permission-granted", role = role.name.as_str(), capability = capability.as_str())
+    } else {
+        tr!(lang, "permission-already-granted", role = role.name.as_str(), capability = capability.as_str())
+    };
+    say_ephemeral(ctx, message).await
+}
+
+/// 移除身分組的能力
+#[poise::command(slash_command, guild_only, rename = "revoke", custom_data = Capability::Admin)]
+pub async fn permissions_revoke(
+    ctx: PoiseContext<'_>,
+    #[description = "能力"] capability: Capability,
+    #[description = "身分組"] role: serenity::all::Role,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let lang = i18n::language(ctx).await;
+
+    let removed = ctx.data().settings.lock().await.update(guild_id, |settings| {
+        let removed = settings
+            .permissions
+            .get_mut(&capability)
+            .is_some_and(|roles| roles.remove(&role.id));
+        settings.permissions.retain(|_, roles| !roles.is_empty());
+        removed
+    })?;
+    let message = if removed {
+        tr!(lang, "permission-revoked", role = role.name.as_str(), capability = capability.as_str())
+    } else {
+        tr!(lang, "permission-not-granted", role = role.name.as_str(), capability = capability.as_str())
+    };
+    say_ephemeral(ctx, message).await
+}
+
+/// 列出每個能力對應的身分組
+#[poise::command(slash_command, guild_only, rename = "list", custom_data = Capability::Admin)]
+pub async fn permissions_list(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let lang = i18n::language(ctx).await;
+
+    let settings = ctx.data().settings.lock().await.get(guild_id);
+    let lines: Vec<String> = Capability::ALL
+        .iter()
+        .map(|capability| {
+            let roles = settings.roles(*capability);
+            let holders = if !roles.is_empty() {
+                format_roles(&roles)
+            } else if capability.open_by_default() {
+                tr!(lang, "permission-everyone")
+            } else {
+                tr!(lang, "permission-managers")
+            };
+            format!("`{}`: {}", capability.as_str(), holders)
+        })
+        .collect();
+
+    say_ephemeral(ctx, lines.join("\n")).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DJ: RoleId = RoleId::new(10);
+    const LISTENER: RoleId = RoleId::new(20);
+
+    fn member<'a>(is_manager: bool, roles: &'a [RoleId], allowed_roles: &'a HashSet<RoleId>) -> Option<GuildMember<'a>> {
+        Some(GuildMember {
+            is_manager,
+            roles,
+            allowed_roles,
+        })
+    }
+
+    #[test]
+    fn owner_is_always_allowed() {
+        let none = HashSet::new();
+        let dj = HashSet::from([DJ]);
+        assert_eq!(decide(Requirement::Owner, true, None), Decision::Allow);
+        assert_eq!(decide(Requirement::Capability(Capability::Admin), true, None), Decision::Allow);
+        assert_eq!(
+            decide(Requirement::Capability(Capability::Admin), true, member(false, &[], &none)),
+            Decision::Allow
+        );
+        assert_eq!(
+            decide(Requirement::Capability(Capability::Control), true, member(false, &[], &dj)),
+            Decision::Allow
+        );
+    }
+
+    #[test]
+    fn unmarked_commands_are_open() {
+        assert_eq!(decide(Requirement::Anyone, false, None), Decision::Allow);
+    }
+
+    #[test]
+    fn owner_only_commands_deny_managers() {
+        let none = HashSet::new();
+        assert_eq!(decide(Requirement::Owner, false, None), Decision::DenyOwnerOnly);
+        assert_eq!(decide(Requirement::Owner, false, member(true, &[], &none)), Decision::DenyOwnerOnly);
+    }
+
+    #[test]
+    fn direct_messages_allow_only_open_capabilities() {
+        assert_eq!(decide(Requirement::Capability(Capability::Control), false, None), Decision::Allow);
+        assert_eq!(decide(Requirement::Capability(Capability::Admin), false, None), Decision::DenyGuildOnly);
+        assert_eq!(decide(Requirement::Capability(Capability::QuizAdmin), false, None), Decision::DenyGuildOnly);
+    }
+
+    #[test]
+    fn managers_bypass_role_settings() {
+        let none = HashSet::new();
+        let dj = HashSet::from([DJ]);
+        assert_eq!(decide(Requirement::Capability(Capability::Admin), false, member(true, &[], &none)), Decision::Allow);
+        assert_eq!(decide(Requirement::Capability(Capability::Control), false, member(true, &[], &dj)), Decision::Allow);
+    }
+
+    #[test]
+    fn without_configured_roles_only_open_capabilities_are_allowed() {
+        let none = HashSet::new();
+        let roles = [LISTENER];
+        assert_eq!(decide(Requirement::Capability(Capability::Queue), false, member(false, &roles, &none)), Decision::Allow);
+        assert_eq!(
+            decide(Requirement::Capability(Capability::Admin), false, member(false, &roles, &none)),
+            Decision::DenyManagersOnly
+        );
+    }
+
+    #[test]
+    fn configured_roles_replace_the_default() {
+        let dj = HashSet::from([DJ]);
+        assert_eq!(
+            decide(Requirement::Capability(Capability::Control), false, member(false, &[LISTENER, DJ], &dj)),
+            Decision::Allow
+        );
+        assert_eq!(decide(Requirement::Capability(Capability::Admin), false, member(false, &[DJ], &dj)), Decision::Allow);
+        assert_eq!(
+            decide(Requirement::Capability(Capability::Control), false, member(false, &[LISTENER], &dj)),
+            Decision::DenyMissingRole
+        );
+    }
+}
@@ -15,12 +15,9 @@ use librespot::playback::{
     decoder::AudioPacket,
     mixer::softmixer::SoftMixer,
     mixer::{Mixer, MixerConfig},
-    player::{Player, PlayerEvent},
+    player::Player,
 };
 
-
-
-use std::sync::{ atomic::{AtomicUsize, Ordering}};
 use librespot::discovery::Discovery;
 
 use std::clone::Clone;
@@ -35,7 +32,6 @@ use futures_util::StreamExt;
 use rubato::{Fft, Resampler, FixedSync};
 use audioadapter_buffers::direct::SequentialSliceOfVecs;
 use symphonia::core::io::MediaSource;
-use lazy_static::lazy_static;
 
 use std::mem::size_of;
 use log::debug;
@@ -46,16 +42,59 @@ pub struct SpotifyPlayer {
     pub emitted_sink: EmittedSink,
     pub session: Session,
     pub spirc: Option<Box<Spirc>>,
+    // Spirc 的背景 task，關閉時等它結束而不是固定等待一段時間
+    spirc_task: Option<tokio::task::JoinHandle<()>>,
     pub player: Option<Arc<Player>>,
     mixer: Arc<SoftMixer>,
     pub bot_autoplay: bool,
     pub device_name: String,
-    credentials: Credentials,
-    _cache_dir: Option<String>,
+    auth: AuthState,
+    // 強制重新認證期間保留的舊憑證，認證失敗時還原
+    previous_credentials: Option<Credentials>,
+    cache_dir: Option<String>,
+    cache_limit: Option<u64>,
     quality: Bitrate,
     last_disconnect_time: Option<Instant>,
 }
 
+// 等待 Spirc task 結束的時間上限
+const SPIRC_SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+// Discovery 等待 Spotify App 連線的時間上限
+const DISCOVERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// 憑證、音量與音訊檔案都存在 `cache_dir` 下，所有 Session 共用同一種設定
+fn open_cache(cache_dir: Option<String>, size_limit: Option<u64>) -> Option<Cache> {
+    match Cache::new(cache_dir.clone(), cache_dir.clone(), cache_dir, size_limit) {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            println!("[快取] ⚠️ 無法開啟快取目錄: {:?}", e);
+            None
+        }
+    }
+}
+
+fn new_player(quality: Bitrate, session: &Session, mixer: &SoftMixer, sink: &EmittedSink) -> Arc<Player> {
+    let player_config = PlayerConfig {
+        bitrate: quality,
+        ..Default::default()
+    };
+    let cloned_sink = sink.clone();
+    Player::new(
+        player_config,
+        session.clone(),
+        mixer.get_soft_volume(),
+        move || Box::new(cloned_sink),
+    )
+}
+
+/// Spotify 的認證狀態，沒有憑證時機器人仍會啟動，等管理員使用 `/spotify_login` 認證
+pub enum AuthState {
+    NeedsAuthentication,
+    // Discovery 服務正在等待 Spotify App 連線
+    Authenticating,
+    Authenticated(Credentials),
+}
+
 pub struct EmittedSink {
     sender: Arc<SyncSender<[f32; 2]>>,
     pub receiver: Arc<Mutex<Receiver<[f32; 2]>>>,
@@ -199,43 +238,10 @@ impl audio_backend::Sink for EmittedSink {
             }
         }
 
-
-        // 只在每 10000 次寫入時打印一次，避免日誌過多
-        lazy_static! {
-            // Ordering::Relaxed 在此處對性能影響最小，適用於簡單計數
-            static ref SAFE_WRITE_COUNT: AtomicUsize = AtomicUsize::new(0);
-        }
-
-        fn log_audio_write() {
-            // 使用 fetch_add 方法原子地增加計數器，並取得舊值
-            let previous_count = SAFE_WRITE_COUNT.fetch_add(1, Ordering::Relaxed);
-            let current_count = previous_count + 1;
-
-            // 檢查是否達到 10000 的倍數 (使用當前值)
-            if current_count % 10000 == 0 {
-                println!("[音訊] 安全地寫入 {} 批次音訊樣本", current_count);
-            }
-        }
-
-        log_audio_write();
         Ok(())
     }
 }
 
-lazy_static! {
-            // Ordering::Relaxed 在此處對性能影響最小，適用於簡單計數
-            static ref SAFE_WRITE_COUNT: AtomicUsize = AtomicUsize::new(0);
-        }
-fn log_audio_write() {
-    // 使用 fetch_add 方法原子地增加計數器，並取得舊值
-    let previous_count = SAFE_WRITE_COUNT.fetch_add(1, Ordering::Relaxed);
-    let current_count = previous_count + 1;
-
-    // 檢查是否達到 10000 的倍數 (使用當前值)
-    if current_count % 10000 == 0 {
-        println!("[音訊] 安全地寫入 {} 批次音訊樣本", current_count);
-    }
-}
 impl io::Read for EmittedSink {
     fn read(&mut self, buff: &mut [u8]) -> io::Result<usize> {
         let sample_size = size_of::<f32>() * 2;
@@ -272,11 +278,6 @@ impl io::Read for EmittedSink {
             bytes_written += sample_size;
         }
 
-        // 每 10000 次讀取打印一次
-
-
-
-        log_audio_write();
         Ok(bytes_written)
     }
 }
@@ -311,64 +312,6 @@ impl Clone for EmittedSink {
 
 
 impl SpotifyPlayer {
-    pub fn on_connection_change<F>(&self, callback: F) -> Option<tokio::task::JoinHandle<()>>
-    where
-        F: Fn(bool, &str) + Send + 'static,  // (is_connected, user_name)
-    {
-        let mut event_channel = self.get_event_channel()?;
-
-        Some(tokio::spawn(async move {
-            while let Some(event) = event_channel.recv().await {
-                match event {
-                    PlayerEvent::SessionConnected { user_name, .. } => {
-                        callback(true, &user_name);
-                    }
-                    PlayerEvent::SessionDisconnected { user_name, .. } => {
-                        callback(false, &user_name);
-                    }
-                    _ => {}
-                }
-            }
-        }))
-    }
-    pub fn get_event_channel(&self) -> Option<tokio::sync::mpsc::UnboundedReceiver<PlayerEvent>> {
-        self.player.as_ref().map(|p| p.get_player_event_channel())
-    }
-    // 新增：啟動事件監聽器
-    pub fn start_event_listener(&self) -> Option<tokio::task::JoinHandle<()>> {
-        let mut event_channel = self.get_event_channel()?;
-
-        Some(tokio::spawn(async move {
-            while let Some(event) = event_channel.recv().await {
-                match event {
-                    PlayerEvent::SessionConnected { connection_id, user_name } => {
-                        println!("✓ 使用者已連線！");
-                        println!("  - 使用者: {}", user_name);
-                        println!("  - Connection ID: {}", connection_id);
-                        // 在這裡做你想做的事，例如開啟音響
-                    }
-                    PlayerEvent::SessionDisconnected { connection_id, user_name } => {
-                        println!("✗ 使用者已斷線");
-                        println!("  - 使用者: {}", user_name);
-                        println!("  - Connection ID: {}", connection_id);
-                        // 在這裡做你想做的事，例如關閉音響
-                    }
-                    PlayerEvent::Playing { track_id, position_ms, .. } => {
-                        println!("▶ 正在播放: {:?} @ {}ms", track_id, position_ms);
-                    }
-                    PlayerEvent::Paused { track_id, position_ms, .. } => {
-                        println!("⏸ 已暫停: {:?} @ {}ms", track_id, position_ms);
-                    }
-                    PlayerEvent::Stopped { track_id, .. } => {
-                        println!("⏹ 已停止: {:?}", track_id);
-                    }
-                    // 其他事件可以忽略或處理
-                    _ => {}
-                }
-            }
-            println!("[Event Listener] 事件通道已關閉");
-        }))
-    }
     pub async fn re_auth(
         cache_dir: Option<String>,
         device_name: &str,
@@ -408,7 +351,11 @@ impl SpotifyPlayer {
         println!();
 
 
-        let credentials = discovery.next().await.expect("無法獲取憑證");
+        // 逾時或 Discovery 服務中斷時回傳錯誤，由呼叫端決定是否重試
+        let credentials = tokio::time::timeout(DISCOVERY_TIMEOUT, discovery.next())
+            .await
+            .map_err(|_| "等待 Spotify 應用連接逾時")?
+            .ok_or("Discovery 服務已中斷")?;
 
         println!("✓ 收到憑證！");
 
@@ -417,13 +364,8 @@ impl SpotifyPlayer {
             println!();
             println!("正在驗證並保存憑證...");
 
-            let cache = Cache::new(
-                Some(cache_path.clone()),
-                Some(cache_path.clone()),
-                Some(cache_path.clone()),
-                None,
-            )
-                .map_err(|e| format!("無法創建 cache: {:?}", e))?;
+            // 只用來保存憑證，不需要音訊檔案的大小上限
+            let cache = open_cache(Some(cache_path.clone()), None).ok_or("無法創建 cache")?;
 
             let session = Session::new(SessionConfig::default(), Some(cache));
 
@@ -448,146 +390,156 @@ impl SpotifyPlayer {
     pub async fn new(
         quality: Bitrate,
         cache_dir: Option<String>,
+        cache_limit: Option<u64>,
         bot_autoplay: bool,
         device_name: String,
 
-    ) -> SpotifyPlayer {
-        let session_config = SessionConfig::default();
-
-        // 4 GB
-        let mut cache_limit: u64 = 10;
-        cache_limit = cache_limit.pow(9);
-        cache_limit *= 4;
-        let cache_dir_for_reauth = cache_dir.clone();
-        let cache = Cache::new(
-            cache_dir.clone(),
-            cache_dir.clone(),
-            cache_dir,
-            Some(cache_limit),
-        )
-        .ok();
-
-        // 首先嘗試從快取中載入憑證
-        // 獲取憑證 - 優先使用快取,沒有則強制重新認證
-        let credentials = if let Some(ref cache) = cache {
-            match cache.credentials() {
-                Some(cached_creds) => {
-                    println!("✓ 使用快取憑證");
-                    cached_creds
-                }
-                None => {
-                    println!("========================================");
-                    println!("未找到快取憑證,需要重新認證");
-                    println!("========================================");
-
-                    match Self::re_auth(cache_dir_for_reauth.clone(), &device_name).await {   //移動後使用的值 [E0382]
-                        Ok(creds) => {
-                            println!("✓ 重新認證成功");
-                            creds
-                        }
-                        Err(e) => {
-                            eprintln!("✗ 重新認證失敗: {:?}", e);
-                            eprintln!("無法繼續,程序退出");
-                            std::process::exit(1);
-                        }
-                    }
-                }
+    ) -> Result<SpotifyPlayer, Box<dyn std::error::Error + Send + Sync>> {
+        let cache = open_cache(cache_dir.clone(), cache_limit);
+
+        // 只從快取載入憑證，沒有憑證時不阻塞啟動，等管理員在 Discord 上重新認證
+        let auth = match cache.as_ref().and_then(|cache| cache.credentials()) {
+            Some(cached_creds) => {
+                println!("✓ 使用快取憑證");
+                AuthState::Authenticated(cached_creds)
             }
-        } else {
-            println!("========================================");
-            println!("警告：沒有設定快取目錄");
-            println!("========================================");
-            println!("將進行一次性認證(憑證不會被保存)");
-
-            match Self::re_auth(None, &device_name).await {
-                Ok(creds) => creds,
-                Err(e) => {
-                    eprintln!("✗ 認證失敗: {:?}", e);
-                    eprintln!("無法繼續,程序退出");
-                    std::process::exit(1);
+            None => {
+                println!("========================================");
+                if cache.is_none() {
+                    println!("警告：沒有設定快取目錄，認證後憑證不會被保存");
+                } else {
+                    println!("未找到快取憑證，需要重新認證");
                 }
+                println!("請在 Discord 使用 /spotify_login 開始認證");
+                println!("========================================");
+                AuthState::NeedsAuthentication
             }
         };
 
-        let session = Session::new(session_config, cache);
-
-        let player_config = PlayerConfig {
-            bitrate: quality,
-            ..Default::default()
-        };
+        let session = Session::new(SessionConfig::default(), cache);
 
         let emitted_sink = EmittedSink::new();
 
-        let cloned_sink = emitted_sink.clone();
-
         let mixer = Arc::new(SoftMixer::open(MixerConfig {
             volume_ctrl: VolumeCtrl::Linear,
             ..MixerConfig::default()
-        }).expect("Failed to open SoftMixer"));
+        }).map_err(|e| format!("無法開啟 SoftMixer: {:?}", e))?);
 
-        let player = Player::new(
-            player_config.clone(),
-            session.clone(),
-            mixer.get_soft_volume(),
-            move || Box::new(cloned_sink),
-        );
+        let player = new_player(quality, &session, &mixer, &emitted_sink);
 
         println!("[初始化] SpotifyPlayer 創建完成，Session 尚未連接");
 
-        SpotifyPlayer {
+        Ok(SpotifyPlayer {
 
             emitted_sink,
             session,
             spirc: None,
+            spirc_task: None,
             player: Some(player),
             mixer,
             bot_autoplay,
             device_name,
-            credentials,
-            _cache_dir: cache_dir_for_reauth,
+            auth,
+            previous_credentials: None,
+            cache_dir,
+            cache_limit,
             quality,
             last_disconnect_time: None,
+        })
+    }
+
+    pub fn auth_state(&self) -> &AuthState {
+        &self.auth
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        matches!(self.auth, AuthState::Authenticated(_))
+    }
+
+    pub fn cache_dir(&self) -> Option<String> {
+        self.cache_dir.clone()
+    }
+
+    /// 開始認證，已經在認證中或已有憑證時回傳 false
+    pub fn begin_auth(&mut self, force: bool) -> bool {
+        match self.auth {
+            AuthState::Authenticating => false,
+            AuthState::Authenticated(_) if !force => false,
+            _ => {
+                self.previous_credentials = match std::mem::replace(&mut self.auth, AuthState::Authenticating) {
+                    AuthState::Authenticated(credentials) => Some(credentials),
+                    _ => None,
+                };
+                true
+            }
         }
     }
+
+    /// 認證結束，失敗時還原原本的憑證，沒有憑證時回到需要認證的狀態讓管理員重試
+    pub fn finish_auth(&mut self, credentials: Option<Credentials>) {
+        let previous = self.previous_credentials.take();
+        self.auth = match (credentials, previous) {
+            (Some(credentials), _) => {
+                // 可能換了帳號，舊的 Session 不能再用
+                self.reset_session();
+                AuthState::Authenticated(credentials)
+            }
+            (None, Some(previous)) => {
+                println!("[認證] ↩️ 繼續使用原本的憑證");
+                AuthState::Authenticated(previous)
+            }
+            (None, None) => AuthState::NeedsAuthentication,
+        };
+    }
+
+    // 建立新的 Session，Player 綁定 Session 所以一起重建
+    fn reset_session(&mut self) {
+        println!("[Spirc] 創建新的 Session...");
+        self.session = Session::new(SessionConfig::default(), open_cache(self.cache_dir.clone(), self.cache_limit));
+        self.emitted_sink.reset();
+        self.player = Some(new_player(self.quality, &self.session, &self.mixer, &self.emitted_sink));
+    }
+    pub fn is_connect_enabled(&self) -> bool {
+        self.spirc.is_some()
+    }
+
     pub async fn enable_connect(&mut self) -> bool {
-        // 如果 Spirc 已存在，先清理
-        if let Some(spirc) = self.spirc.take() {
-            println!("[Spirc] 關閉舊的 Spirc...");
-            let _ = spirc.shutdown();
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        let AuthState::Authenticated(ref credentials) = self.auth else {
+            println!("[Spirc] ⚠️ 尚未完成 Spotify 認證，請使用 /spotify_login");
+            return false;
+        };
+        let credentials = credentials.clone();
+
+        if let Some(time) = self.last_disconnect_time {
+            println!("[Spirc] 距離上次停用已過 {} 秒", time.elapsed().as_secs());
         }
 
-        // ========== 關鍵：創建全新的 Session ==========
-        println!("[Spirc] 創建新的 Session...");
-        let cache = Cache::new(
-            self._cache_dir.clone(),
-            self._cache_dir.clone(),
-            self._cache_dir.clone(),
-            Some(4 * 1024 * 1024 * 1024), // 4GB
-        ).ok();
+        // 如果 Spirc 已存在，先清理
+        self.shutdown_spirc().await;
 
-        self.session = Session::new(SessionConfig::default(), cache);
-        // ============================================
+        // Session 仍然有效時沿用，只重建 Spirc；斷線或被關閉時才建立新的 Session
+        if self.session.is_invalid() || self.player.is_none() {
+            self.reset_session();
+        } else {
+            println!("[Spirc] 沿用目前的 Session");
+            self.emitted_sink.reset();
+        }
 
-        // 重設 Sink
-        self.emitted_sink.reset();
+        if self.start_spirc(credentials.clone()).await {
+            return true;
+        }
+
+        // 沿用的 Session 可能已經無法使用，換一個新的再試一次
+        println!("[Spirc] 使用新的 Session 重試...");
+        self.reset_session();
+        self.start_spirc(credentials).await
+    }
 
-        // 創建新的 Player（必須用新的 Session）
-        println!("[Spirc] 創建新的 Player...");
-        let player_config = PlayerConfig {
-            bitrate: self.quality,
-            ..Default::default()
+    async fn start_spirc(&mut self, credentials: Credentials) -> bool {
+        let Some(player) = self.player.clone() else {
+            return false;
         };
-        let cloned_sink = self.emitted_sink.clone();
-        let new_player = Player::new(
-            player_config,
-            self.session.clone(),  // 使用新的 Session
-            self.mixer.get_soft_volume(),
-            move || Box::new(cloned_sink),
-        );
-        self.player = Some(new_player.clone());
-
-        // 創建新的 Spirc
+
         println!("[Spirc] 創建新的 Spirc，裝置名稱: {}", self.device_name);
         let config = ConnectConfig {
             name: self.device_name.clone(),
@@ -601,18 +553,18 @@ impl SpotifyPlayer {
         match Spirc::new(
             config,
             self.session.clone(),
-            self.credentials.clone(),
-            new_player,
+            credentials,
+            player,
             self.mixer.clone(),
         ).await {
             Ok((spirc, task)) => {
                 println!("[Spirc] ✓ Spirc 創建成功");
 
-                tokio::spawn(async move {
+                self.spirc_task = Some(tokio::spawn(async move {
                     println!("[Spirc] Spirc task 開始運行");
                     task.await;
                     println!("[Spirc] Spirc task 結束");
-                });
+                }));
 
                 self.spirc = Some(Box::new(spirc));
                 self.last_disconnect_time = None;
@@ -627,6 +579,14 @@ impl SpotifyPlayer {
         }
     }
     pub async fn disable_connect(&mut self) {
+        self.shutdown_spirc().await;
+
+        self.last_disconnect_time = Some(Instant::now());
+        println!("[Spirc] ✓ Spotify Connect 已停用");
+    }
+
+    // 關閉 Spirc 並等待它的 task 結束
+    async fn shutdown_spirc(&mut self) {
         if let Some(spirc) = self.spirc.take() {  // 使用 take() 移除
             println!("[Spirc] 關閉 Spirc...");
 
@@ -634,13 +594,13 @@ impl SpotifyPlayer {
             if let Err(e) = spirc.shutdown() {
                 println!("[Spirc] shutdown 錯誤: {:?}", e);
             }
-
-            // 等待關閉完成
-            tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
         }
 
-        self.last_disconnect_time = Some(Instant::now());
-        println!("[Spirc] ✓ Spotify Connect 已停用");
+        if let Some(task) = self.spirc_task.take() {
+            if tokio::time::timeout(SPIRC_SHUTDOWN_TIMEOUT, task).await.is_err() {
+                println!("[Spirc] ⚠️ Spirc task 超過 {} 秒仍未結束", SPIRC_SHUTDOWN_TIMEOUT.as_secs());
+            }
+        }
     }
 }
 
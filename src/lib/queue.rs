@@ -0,0 +1,456 @@
+///lib/queue.rs
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::StreamExt;
+use http::{Method, Request};
+use librespot::core::session::Session;
+use serde::Deserialize;
+use serenity::all::{
+    ComponentInteractionCollector, ComponentInteractionDataKind, CreateActionRow, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind,
+    CreateSelectMenuOption,
+};
+
+use crate::lib::i18n::{self, tr};
+use crate::lib::permissions::Capability;
+use crate::lib::settings::Language;
+use crate::{Data, Error, PoiseContext, say_ephemeral};
+
+const API_BASE: &str = "https://api.spotify.com/v1";
+const SEARCH_LIMIT: usize = 10;
+const QUEUE_LIST_LIMIT: usize = 10;
+// 等待使用者從選單中選擇的時間
+const SELECT_TIME: Duration = Duration::from_secs(60);
+// Discord 選單選項的長度上限
+const MAX_OPTION_LEN: usize = 100;
+
+#[derive(Deserialize, Clone, Debug)]
+struct ApiArtist {
+    name: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct ApiAlbum {
+    name: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct ApiShow {
+    name: String,
+}
+
+/// Web API 回傳的曲目或單集，單集沒有演出者與專輯
+#[derive(Deserialize, Clone, Debug)]
+struct ApiItem {
+    name: String,
+    uri: String,
+    #[serde(default)]
+    artists: Vec<ApiArtist>,
+    album: Option<ApiAlbum>,
+    show: Option<ApiShow>,
+    #[serde(default)]
+    duration_ms: u64,
+}
+
+impl ApiItem {
+    fn subtitle(&self) -> String {
+        match self.show {
+            Some(ref show) => show.name.clone(),
+            None => self
+                .artists
+                .iter()
+                .map(|artist| artist.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchPage {
+    // 已下架的項目會是 null
+    #[serde(default)]
+    items: Vec<Option<ApiItem>>,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    tracks: Option<SearchPage>,
+}
+
+#[derive(Deserialize)]
+struct QueueResponse {
+    currently_playing: Option<ApiItem>,
+    #[serde(default)]
+    queue: Vec<ApiItem>,
+}
+
+// 只保留查詢字串中安全的字元，其他的以 %XX 編碼
+fn encode_query(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// 把 open.spotify.com 的網址或 `spotify:` URI 轉成 `spotify:track:<id>` 形式，
+/// 只接受曲目與單集
+pub fn parse_spotify_uri(input: &str) -> Option<String> {
+    let input = input.trim();
+
+    let (kind, id) = if let Some(rest) = input.strip_prefix("spotify:") {
+        rest.split_once(':')?
+    } else {
+        let path = input
+            .strip_prefix("https://")
+            .or_else(|| input.strip_prefix("http://"))
+            .unwrap_or(input)
+            .strip_prefix("open.spotify.com/")?;
+        // 去掉 ?si=... 等參數
+        let path = path.split(['?', '#']).next()?;
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+        let mut kind = segments.next()?;
+        // 網址可能帶有語系前綴，例如 /intl-ja/track/...
+        if kind.starts_with("intl-") {
+            kind = segments.next()?;
+        }
+        (kind, segments.next()?)
+    };
+
+    let valid_id = id.len() == 22 && id.chars().all(|c| c.is_ascii_alphanumeric());
+    if !matches!(kind, "track" | "episode") || !valid_id {
+        return None;
+    }
+    Some(format!("spotify:{}:{}", kind, id))
+}
+
+// 透過 SpotifyPlayer 的 Session 取得權杖並呼叫 Web API
+async fn api_request(session: &Session, method: Method, path: &str) -> Result<Bytes, Error> {
+    let token = session.login5().auth_token().await?;
+    let request = Request::builder()
+        .method(method)
+        .uri(format!("{}{}", API_BASE, path))
+        .header("Authorization", format!("Bearer {}", token.access_token))
+        .header("Content-Length", "0")
+        .body(Bytes::new())?;
+    Ok(session.http_client().request_body(request).await?)
+}
+
+async fn search_tracks(session: &Session, query: &str) -> Result<Vec<ApiItem>, Error> {
+    let path = format!("/search?type=track&limit={}&q={}", SEARCH_LIMIT, encode_query(query));
+    let body = api_request(session, Method::GET, &path).await?;
+    let response: SearchResponse = serde_json::from_slice(&body)?;
+    Ok(response
+        .tracks
+        .map(|page| page.items.into_iter().flatten().collect())
+        .unwrap_or_default())
+}
+
+async fn add_to_queue(session: &Session, uri: &str) -> Result<(), Error> {
+    // 指定 device_id，確保加入的是機器人的佇列而不是使用者其他裝置的
+    let path = format!(
+        "/me/player/queue?uri={}&device_id={}",
+        encode_query(uri),
+        encode_query(session.device_id())
+    );
+    api_request(session, Method::POST, &path).await?;
+    Ok(())
+}
+
+async fn fetch_queue(session: &Session) -> Result<QueueResponse, Error> {
+    let body = api_request(session, Method::GET, "/me/player/queue").await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+// 只在複製 Session 時鎖住 player；Spotify Connect 關閉時沒有佇列可以操作
+async fn connect_session(data: &Data) -> Option<Session> {
+    let player = data.player.lock().await;
+    if !player.is_connect_enabled() {
+        return None;
+    }
+    Some(player.session.clone())
+}
+
+/// Spotify Connect 無法使用時的說明訊息 ID
+pub(crate) async fn connect_unavailable(data: &Data) -> &'static str {
+    if data.player.lock().await.is_authenticated() {
+        "connect-not-enabled"
+    } else {
+        "connect-needs-auth"
+    }
+}
+
+fn search_menu(lang: Language, custom_id: &str, results: &[ApiItem], disabled: bool) -> Vec<CreateActionRow> {
+    let options = results
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            CreateSelectMenuOption::new(truncate(&item.name, MAX_OPTION_LEN), i.to_string()).description(
+                truncate(
+                    &format!("{} · {}", item.subtitle(), format_duration(item.duration_ms)),
+                    MAX_OPTION_LEN,
+                ),
+            )
+        })
+        .collect();
+
+    vec![CreateActionRow::SelectMenu(
+        CreateSelectMenu::new(custom_id, CreateSelectMenuKind::String { options })
+            .placeholder(tr!(lang, "search-placeholder"))
+            .disabled(disabled),
+    )]
+}
+
+/// 搜尋 Spotify 曲目，從選單中選擇後加入播放佇列
+#[poise::command(slash_command, guild_only, custom_data = Capability::Queue)]
+pub async fn search(
+    ctx: PoiseContext<'_>,
+    #[description = "搜尋關鍵字"] query: String,
+) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let Some(session) = connect_session(ctx.data()).await else {
+        return say_ephemeral(ctx, tr!(lang, connect_unavailable(ctx.data()).await)).await;
+    };
+
+    ctx.defer().await?;
+    let results = match search_tracks(&session, &query).await {
+        Ok(results) => results,
+        Err(e) => {
+            println!("[佇列] ⚠️ 搜尋失敗: {:?}", e);
+            ctx.say(tr!(lang, "search-failed")).await?;
+            return Ok(());
+        }
+    };
+    if results.is_empty() {
+        ctx.say(tr!(lang, "search-no-results", query = query.as_str())).await?;
+        return Ok(());
+    }
+
+    let custom_id = format!("search:{}", ctx.id());
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .content(tr!(lang, "search-results", query = query.as_str()))
+                .components(search_menu(lang, &custom_id, &results, false)),
+        )
+        .await?;
+    let message_id = reply.message().await?.id;
+
+    let mut collector = ComponentInteractionCollector::new(ctx.serenity_context())
+        .message_id(message_id)
+        .custom_ids(vec![custom_id.clone()])
+        .timeout(SELECT_TIME)
+        .stream();
+
+    let mut chosen = None;
+    while let Some(interaction) = collector.next().await {
+        // 只有下指令的人可以選擇
+        if interaction.user.id != ctx.author().id {
+            let response = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(tr!(lang, "search-not-yours"))
+                    .ephemeral(true),
+            );
+            if let Err(e) = interaction.create_response(ctx.serenity_context(), response).await {
+                println!("[佇列] ⚠️ 無法回應選單互動: {:?}", e);
+            }
+            continue;
+        }
+
+        let ComponentInteractionDataKind::StringSelect { ref values } = interaction.data.kind else {
+            continue;
+        };
+        let Some(item) = values
+            .first()
+            .and_then(|value| value.parse::<usize>().ok())
+            .and_then(|i| results.get(i))
+        else {
+            continue;
+        };
+
+        let content = match add_to_queue(&session, &item.uri).await {
+            Ok(()) => {
+                println!("[佇列] ➕ {} 加入了 {}", ctx.author().name, item.name);
+                tr!(lang, "queue-added-track", title = item.name.as_str(), artists = item.subtitle())
+            }
+            Err(e) => {
+                println!("[佇列] ⚠️ 無法加入佇列: {:?}", e);
+                tr!(lang, "queue-add-failed")
+            }
+        };
+
+        let response = CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .content(content)
+                .components(search_menu(lang, &custom_id, &results, true)),
+        );
+        if let Err(e) = interaction.create_response(ctx.serenity_context(), response).await {
+            println!("[佇列] ⚠️ 無法回應選單互動: {:?}", e);
+        }
+        chosen = Some(item);
+        break;
+    }
+
+    // 逾時沒有選擇時停用選單
+    if chosen.is_none() {
+        reply
+            .edit(
+                ctx,
+                poise::CreateReply::default()
+                    .content(tr!(lang, "search-timeout", query = query.as_str()))
+                    .components(search_menu(lang, &custom_id, &results, true)),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// 播放佇列
+#[poise::command(slash_command, guild_only, subcommands("queue_add", "queue_list"))]
+pub async fn queue(_ctx: PoiseContext<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 以 Spotify 網址或 URI 將曲目加入播放佇列
+#[poise::command(slash_command, guild_only, rename = "add", custom_data = Capability::Queue)]
+pub async fn queue_add(
+    ctx: PoiseContext<'_>,
+    #[description = "Spotify 曲目或單集的網址或 URI"] url: String,
+) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let Some(uri) = parse_spotify_uri(&url) else {
+        return say_ephemeral(ctx, tr!(lang, "queue-invalid-url")).await;
+    };
+    let Some(session) = connect_session(ctx.data()).await else {
+        return say_ephemeral(ctx, tr!(lang, connect_unavailable(ctx.data()).await)).await;
+    };
+
+    ctx.defer().await?;
+    match add_to_queue(&session, &uri).await {
+        Ok(()) => {
+            println!("[佇列] ➕ {} 加入了 {}", ctx.author().name, uri);
+            ctx.say(tr!(lang, "queue-added-url", url = url.trim())).await?;
+        }
+        Err(e) => {
+            println!("[佇列] ⚠️ 無法加入佇列: {:?}", e);
+            ctx.say(tr!(lang, "queue-add-failed")).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 顯示接下來要播放的曲目
+#[poise::command(slash_command, guild_only, rename = "list")]
+pub async fn queue_list(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let Some(session) = connect_session(ctx.data()).await else {
+        return say_ephemeral(ctx, tr!(lang, connect_unavailable(ctx.data()).await)).await;
+    };
+
+    ctx.defer().await?;
+    let queue = match fetch_queue(&session).await {
+        Ok(queue) => queue,
+        Err(e) => {
+            println!("[佇列] ⚠️ 無法取得佇列: {:?}", e);
+            ctx.say(tr!(lang, "queue-list-failed")).await?;
+            return Ok(());
+        }
+    };
+
+    let mut description = match queue.currently_playing {
+        Some(ref item) => format!("▶️ **{}** - {}\n\n", item.name, item.subtitle()),
+        None => format!("{}\n\n", tr!(lang, "queue-nothing-playing")),
+    };
+    if queue.queue.is_empty() {
+        description.push_str(&tr!(lang, "queue-empty"));
+    }
+    for (i, item) in queue.queue.iter().take(QUEUE_LIST_LIMIT).enumerate() {
+        description.push_str(&format!(
+            "`{:>2}.` {} - {} · {}\n",
+            i + 1,
+            item.name,
+            item.subtitle(),
+            format_duration(item.duration_ms),
+        ));
+    }
+    if queue.queue.len() > QUEUE_LIST_LIMIT {
+        description.push_str(&tr!(lang, "queue-more", count = queue.queue.len() - QUEUE_LIST_LIMIT));
+    }
+
+    ctx.send(
+        poise::CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(tr!(lang, "queue-title"))
+                .description(description)
+                .colour(0x1DB954),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACK: &str = "spotify:track:3eR23VReFzcdmS7TYCrhCe";
+
+    #[test]
+    fn parses_spotify_uris() {
+        assert_eq!(parse_spotify_uri(TRACK).as_deref(), Some(TRACK));
+        assert_eq!(
+            parse_spotify_uri("  spotify:episode:512ojhOuo1ktJprKbVcKyQ  ").as_deref(),
+            Some("spotify:episode:512ojhOuo1ktJprKbVcKyQ")
+        );
+    }
+
+    #[test]
+    fn parses_open_spotify_urls() {
+        for url in [
+            "https://open.spotify.com/track/3eR23VReFzcdmS7TYCrhCe",
+            "http://open.spotify.com/track/3eR23VReFzcdmS7TYCrhCe",
+            "open.spotify.com/track/3eR23VReFzcdmS7TYCrhCe",
+            "https://open.spotify.com/track/3eR23VReFzcdmS7TYCrhCe?si=abc123&utm_source=copy-link",
+            "https://open.spotify.com/track/3eR23VReFzcdmS7TYCrhCe/?si=abc123",
+            "https://open.spotify.com/track/3eR23VReFzcdmS7TYCrhCe#t=30",
+            "https://open.spotify.com/intl-ja/track/3eR23VReFzcdmS7TYCrhCe?si=abc123",
+        ] {
+            assert_eq!(parse_spotify_uri(url).as_deref(), Some(TRACK), "{}", url);
+        }
+    }
+
+    #[test]
+    fn rejects_other_kinds_and_malformed_ids() {
+        for input in [
+            "spotify:album:3eR23VReFzcdmS7TYCrhCe",
+            "https://open.spotify.com/playlist/3eR23VReFzcdmS7TYCrhCe",
+            "https://open.spotify.com/track/short",
+            "https://open.spotify.com/track/3eR23VReFzcdmS7TYCrhC!",
+            "https://open.spotify.com/track/?si=3eR23VReFzcdmS7TYCrhCe",
+            "https://example.com/track/3eR23VReFzcdmS7TYCrhCe",
+            "spotify:track",
+            "",
+        ] {
+            assert_eq!(parse_spotify_uri(input), None, "{}", input);
+        }
+    }
+}
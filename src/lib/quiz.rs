@@ -0,0 +1,1251 @@
+///lib/quiz.rs
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use rand::seq::{IteratorRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    ButtonStyle, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditMessage,
+    GuildId, Message, UserId,
+};
+use tokio::sync::Mutex;
+
+use crate::lib::i18n::{self, tr};
+use crate::lib::quiz_generator::ensure_question;
+use crate::lib::settings::Language;
+use crate::lib::storage::backup;
+use crate::{Error, PoiseContext};
+
+const QUIZ_BANK_FILE: &str = "quiz_bank.json";
+const FEEDBACK_LOG_FILE: &str = "feedback_logs.json";
+const BANK_VERSION: &str = "1.0";
+// 每一題的作答時間
+pub(crate) const ANSWER_TIME: Duration = Duration::from_secs(20);
+// 公布結果後開放評價的時間
+const FEEDBACK_TIME: Duration = Duration::from_secs(60);
+const OPTION_LABELS: [&str; 4] = ["A", "B", "C", "D"];
+// Discord 按鈕文字的長度上限（字元）
+const MAX_BUTTON_LABEL: usize = 80;
+
+// 題目品質報告的判定門檻
+const MIN_VOTES_FOR_REPORT: u32 = 5;
+const MIN_PLAYS_FOR_REPORT: u32 = 5;
+const MAX_DISLIKE_RATIO: f64 = 0.5;
+const MAX_CORRECT_RATE: f64 = 0.95;
+const MIN_CORRECT_RATE: f64 = 0.05;
+const LOW_QUALITY_SCORE: f64 = 0.3;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, poise::ChoiceParameter)]
+#[serde(rename_all = "snake_case")]
+pub enum QuizType {
+    #[name = "猜動漫"]
+    #[name_localized("en-US", "Guess the anime")]
+    #[name_localized("en-GB", "Guess the anime")]
+    Anime,
+    #[name = "猜歌手"]
+    #[name_localized("en-US", "Guess the artist")]
+    #[name_localized("en-GB", "Guess the artist")]
+    Artist,
+    #[name = "猜歌名"]
+    #[name_localized("en-US", "Guess the song")]
+    #[name_localized("en-GB", "Guess the song")]
+    SongName,
+}
+
+impl QuizType {
+    /// 自動產生的題目使用的訊息 ID
+    pub fn generated_question_id(&self) -> &'static str {
+        match self {
+            QuizType::Anime => "quiz-generated-anime",
+            QuizType::Artist => "quiz-generated-artist",
+            QuizType::SongName => "quiz-generated-song-name",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, poise::ChoiceParameter)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    #[name = "簡單"]
+    #[name_localized("en-US", "Easy")]
+    #[name_localized("en-GB", "Easy")]
+    Easy,
+    #[name = "普通"]
+    #[name_localized("en-US", "Medium")]
+    #[name_localized("en-GB", "Medium")]
+    Medium,
+    #[name = "困難"]
+    #[name_localized("en-US", "Hard")]
+    #[name_localized("en-GB", "Hard")]
+    Hard,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuizSource {
+    Manual,
+    AiGenerated,
+    // 由 Spotify Metadata 離線產生
+    Metadata,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct QuizFeedback {
+    pub likes: u32,
+    pub dislikes: u32,
+    pub total_plays: u32,
+    pub correct_rate: f64,
+}
+
+/// 題目不符合題庫格式的原因，回覆時依照語言轉成訊息
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuizProblem {
+    // 必填欄位是空的，內容為欄位名稱
+    EmptyField(&'static str),
+    OptionCount,
+    BlankOption,
+    DuplicateOption,
+    AnswerNotInOptions,
+    // 數值必須介於 0 到 1，內容為欄位名稱
+    OutOfRange(&'static str),
+    DuplicateId,
+    UnsupportedVersion(String),
+    NotFound(String),
+}
+
+impl QuizProblem {
+    pub fn message(&self, lang: Language) -> String {
+        match self {
+            QuizProblem::EmptyField(field) => tr!(lang, "quiz-problem-empty", field = *field),
+            QuizProblem::OptionCount => tr!(lang, "quiz-problem-option-count", count = OPTION_LABELS.len()),
+            QuizProblem::BlankOption => tr!(lang, "quiz-problem-blank-option"),
+            QuizProblem::DuplicateOption => tr!(lang, "quiz-problem-duplicate-option"),
+            QuizProblem::AnswerNotInOptions => tr!(lang, "quiz-problem-answer-not-in-options"),
+            QuizProblem::OutOfRange(field) => tr!(lang, "quiz-problem-out-of-range", field = *field),
+            QuizProblem::DuplicateId => tr!(lang, "quiz-problem-duplicate-id"),
+            QuizProblem::UnsupportedVersion(version) => {
+                tr!(lang, "quiz-problem-unsupported-version", version = version.as_str())
+            }
+            QuizProblem::NotFound(id) => tr!(lang, "quiz-not-found", id = id.as_str()),
+        }
+    }
+}
+
+// 終端機的紀錄固定使用繁體中文
+impl std::fmt::Display for QuizProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message(Language::ZhTw))
+    }
+}
+
+/// 品質報告標記題目的原因
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QualityFlag {
+    DislikeRatio(f64),
+    TooEasy(f64),
+    TooHard(f64),
+    LowScore(f64),
+}
+
+impl QualityFlag {
+    pub fn message(&self, lang: Language) -> String {
+        match *self {
+            QualityFlag::DislikeRatio(ratio) => {
+                tr!(lang, "quiz-flag-dislike-ratio", percent = format!("{:.0}", ratio * 100.0))
+            }
+            QualityFlag::TooEasy(rate) => tr!(lang, "quiz-flag-too-easy", percent = format!("{:.0}", rate * 100.0)),
+            QualityFlag::TooHard(rate) => tr!(lang, "quiz-flag-too-hard", percent = format!("{:.0}", rate * 100.0)),
+            QualityFlag::LowScore(score) => tr!(lang, "quiz-flag-low-score", score = format!("{:.2}", score)),
+        }
+    }
+}
+
+/// `quiz_bank.json` 中的一道題目，欄位對應 `todolist.md` 的題庫結構
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuizQuestion {
+    pub id: String,
+    pub spotify_track_id: String,
+    pub track_name: String,
+    pub artist: String,
+    pub quiz_type: QuizType,
+    pub question: String,
+    pub correct_answer: String,
+    pub options: Vec<String>,
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub feedback: QuizFeedback,
+    pub source: QuizSource,
+    pub created_at: DateTime<Utc>,
+    pub ai_confidence: Option<f64>,
+}
+
+impl QuizQuestion {
+    /// 檢查題目是否符合題庫格式，回傳所有問題
+    pub fn validate(&self) -> Vec<QuizProblem> {
+        let mut problems = Vec::new();
+
+        if self.id.trim().is_empty() {
+            problems.push(QuizProblem::EmptyField("id"));
+        }
+        if self.spotify_track_id.trim().is_empty() {
+            problems.push(QuizProblem::EmptyField("spotify_track_id"));
+        }
+        if self.question.trim().is_empty() {
+            problems.push(QuizProblem::EmptyField("question"));
+        }
+        if self.options.len() != OPTION_LABELS.len() {
+            problems.push(QuizProblem::OptionCount);
+        }
+        if self.options.iter().any(|o| o.trim().is_empty()) {
+            problems.push(QuizProblem::BlankOption);
+        }
+        let unique: HashSet<&String> = self.options.iter().collect();
+        if unique.len() != self.options.len() {
+            problems.push(QuizProblem::DuplicateOption);
+        }
+        if !self.options.contains(&self.correct_answer) {
+            problems.push(QuizProblem::AnswerNotInOptions);
+        }
+        if !(0.0..=1.0).contains(&self.feedback.correct_rate) {
+            problems.push(QuizProblem::OutOfRange("feedback.correct_rate"));
+        }
+        if let Some(confidence) = self.ai_confidence {
+            if !(0.0..=1.0).contains(&confidence) {
+                problems.push(QuizProblem::OutOfRange("ai_confidence"));
+            }
+        }
+
+        problems
+    }
+
+    /// 顯示的題目文字：自動產生且沒有被 `/quiz_edit` 改過的題目依照題型翻譯，其他題目使用題庫中的文字
+    pub fn question_text(&self, lang: Language) -> String {
+        if self.source == QuizSource::Metadata && self.has_generated_text() {
+            tr!(lang, self.quiz_type.generated_question_id())
+        } else {
+            self.question.clone()
+        }
+    }
+
+    // 題庫中保存的是預設語言的產生文字，只改題型時仍視為自動產生的文字
+    fn has_generated_text(&self) -> bool {
+        [QuizType::Anime, QuizType::Artist, QuizType::SongName]
+            .iter()
+            .any(|quiz_type| self.question == tr!(Language::default(), quiz_type.generated_question_id()))
+    }
+
+    /// `(likes - dislikes * 2) / max(total_plays, 1)`
+    pub fn quality_score(&self) -> f64 {
+        let feedback = &self.feedback;
+        (feedback.likes as f64 - feedback.dislikes as f64 * 2.0) / feedback.total_plays.max(1) as f64
+    }
+
+    /// 品質報告中需要注意的原因，沒有問題時回傳空陣列
+    pub fn quality_flags(&self) -> Vec<QualityFlag> {
+        let feedback = &self.feedback;
+        let mut flags = Vec::new();
+
+        let votes = feedback.likes + feedback.dislikes;
+        if votes >= MIN_VOTES_FOR_REPORT {
+            let dislike_ratio = feedback.dislikes as f64 / votes as f64;
+            if dislike_ratio > MAX_DISLIKE_RATIO {
+                flags.push(QualityFlag::DislikeRatio(dislike_ratio));
+            }
+        }
+        if feedback.total_plays >= MIN_PLAYS_FOR_REPORT {
+            if feedback.correct_rate > MAX_CORRECT_RATE {
+                flags.push(QualityFlag::TooEasy(feedback.correct_rate));
+            } else if feedback.correct_rate < MIN_CORRECT_RATE {
+                flags.push(QualityFlag::TooHard(feedback.correct_rate));
+            }
+            if self.quality_score() < LOW_QUALITY_SCORE {
+                flags.push(QualityFlag::LowScore(self.quality_score()));
+            }
+        }
+
+        flags
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuizBank {
+    pub version: String,
+    pub last_updated: DateTime<Utc>,
+    pub quiz_bank: Vec<QuizQuestion>,
+    // spotify_track_id -> quiz_bank 的索引，只包含可以出題的題目
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+    // 不符合格式的題目仍保留在題庫中，但不會出題，修正後才會重新加入
+    #[serde(skip)]
+    invalid: BTreeMap<usize, Vec<QuizProblem>>,
+}
+
+impl Default for QuizBank {
+    fn default() -> Self {
+        QuizBank {
+            version: BANK_VERSION.to_string(),
+            last_updated: Utc::now(),
+            quiz_bank: Vec::new(),
+            index: HashMap::new(),
+            invalid: BTreeMap::new(),
+        }
+    }
+}
+
+impl QuizBank {
+    /// 檢查整個題庫，回傳 `(題目 id, 問題)` 列表
+    pub fn validate(&self) -> Vec<(String, QuizProblem)> {
+        let mut problems = Vec::new();
+
+        if self.version != BANK_VERSION {
+            problems.push((String::new(), QuizProblem::UnsupportedVersion(self.version.clone())));
+        }
+
+        let mut seen = HashSet::new();
+        for question in &self.quiz_bank {
+            if !seen.insert(&question.id) {
+                problems.push((question.id.clone(), QuizProblem::DuplicateId));
+            }
+            for problem in question.validate() {
+                problems.push((question.id.clone(), problem));
+            }
+        }
+
+        problems
+    }
+
+    /// 重新檢查題目並建立索引，每次修改題庫後都要呼叫
+    fn reindex(&mut self) {
+        let mut seen = HashSet::new();
+        self.invalid = self
+            .quiz_bank
+            .iter()
+            .enumerate()
+            .filter_map(|(i, question)| {
+                let mut problems = question.validate();
+                if !seen.insert(&question.id) {
+                    problems.push(QuizProblem::DuplicateId);
+                }
+                (!problems.is_empty()).then_some((i, problems))
+            })
+            .collect();
+
+        self.index = self
+            .quiz_bank
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.invalid.contains_key(i))
+            .map(|(i, q)| (q.spotify_track_id.clone(), i))
+            .collect();
+    }
+
+    /// 可以出題的題目
+    fn playable(&self) -> impl Iterator<Item = &QuizQuestion> {
+        self.quiz_bank
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.invalid.contains_key(i))
+            .map(|(_, q)| q)
+    }
+
+    /// 不符合格式而不會出題的題目與原因
+    fn invalid_questions(&self) -> impl Iterator<Item = (&QuizQuestion, &Vec<QuizProblem>)> {
+        self.invalid.iter().map(|(&i, problems)| (&self.quiz_bank[i], problems))
+    }
+
+    pub fn find_by_track(&self, track_id: &str) -> Option<&QuizQuestion> {
+        self.index.get(track_id).map(|&i| &self.quiz_bank[i])
+    }
+
+    fn find_by_id_mut(&mut self, id: &str) -> Option<&mut QuizQuestion> {
+        self.quiz_bank.iter_mut().find(|q| q.id == id)
+    }
+
+    /// 產生下一個 `quiz_XXX` 格式的題目 id
+    fn next_id(&self) -> String {
+        let next = self
+            .quiz_bank
+            .iter()
+            .filter_map(|q| q.id.strip_prefix("quiz_")?.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        format!("quiz_{:03}", next)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackType {
+    Like,
+    Dislike,
+}
+
+/// `feedback_logs.json` 中的一筆評價紀錄
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FeedbackLog {
+    pub log_id: String,
+    pub quiz_id: String,
+    pub user_id: UserId,
+    pub guild_id: GuildId,
+    pub feedback_type: FeedbackType,
+    pub user_answer: Option<String>,
+    pub is_correct: Option<bool>,
+    pub answer_time_ms: Option<u64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FeedbackLogFile {
+    version: String,
+    feedback_logs: Vec<FeedbackLog>,
+}
+
+/// `/quiz_report` 的內容
+pub struct QualityReport {
+    pub total: usize,
+    pub flagged: Vec<(QuizQuestion, Vec<QualityFlag>)>,
+    pub invalid: Vec<(QuizQuestion, Vec<QuizProblem>)>,
+}
+
+/// 一位使用者在一輪遊戲中的作答
+#[derive(Clone, Debug)]
+pub struct QuizAnswer {
+    pub user_id: UserId,
+    pub choice: usize,
+    pub correct: bool,
+    pub elapsed_ms: u64,
+}
+
+pub struct QuizState {
+    path: Option<PathBuf>,
+    feedback_path: Option<PathBuf>,
+    bank: Mutex<QuizBank>,
+    feedback_logs: Mutex<Vec<FeedbackLog>>,
+    // 正在進行遊戲的伺服器，防止重複觸發
+    active_games: Mutex<HashSet<GuildId>>,
+}
+
+impl QuizState {
+    /// 從 `cache_dir` 載入題庫，檔案不存在時使用空題庫
+    pub fn load(cache_dir: Option<&str>) -> QuizState {
+        let path = cache_dir.map(|dir| Path::new(dir).join(QUIZ_BANK_FILE));
+        let feedback_path = cache_dir.map(|dir| Path::new(dir).join(FEEDBACK_LOG_FILE));
+
+        let mut bank = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => match serde_json::from_str::<QuizBank>(&content) {
+                Ok(bank) => bank,
+                Err(e) => {
+                    println!("[猜歌] ⚠️ 無法解析 {}: {:?}", QUIZ_BANK_FILE, e);
+                    backup(path.as_deref(), "corrupt");
+                    QuizBank::default()
+                }
+            },
+            _ => QuizBank::default(),
+        };
+        if bank.version != BANK_VERSION {
+            println!("[猜歌] ⚠️ 題庫版本 {} 與目前支援的 {} 不同", bank.version, BANK_VERSION);
+        }
+        bank.reindex();
+        for (question, problems) in bank.invalid_questions() {
+            for problem in problems {
+                println!("[猜歌] ⚠️ 題目 {} 不會出題: {}", question.id, problem);
+            }
+        }
+
+        let feedback_logs = match feedback_path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => match serde_json::from_str::<FeedbackLogFile>(&content) {
+                Ok(file) => file.feedback_logs,
+                Err(e) => {
+                    println!("[猜歌] ⚠️ 無法解析 {}: {:?}", FEEDBACK_LOG_FILE, e);
+                    backup(feedback_path.as_deref(), "corrupt");
+                    Vec::new()
+                }
+            },
+            _ => Vec::new(),
+        };
+
+        println!("[猜歌] 已載入 {} 道題目", bank.quiz_bank.len());
+
+        QuizState {
+            path,
+            feedback_path,
+            bank: Mutex::new(bank),
+            feedback_logs: Mutex::new(feedback_logs),
+            active_games: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// 根據 Spotify Track ID 選題，沒有對應題目時隨機選一題
+    async fn pick_question(&self, track_id: &str) -> Option<QuizQuestion> {
+        let bank = self.bank.lock().await;
+        bank.find_by_track(track_id)
+            .or_else(|| bank.playable().choose(&mut rand::thread_rng()))
+            .cloned()
+    }
+
+    /// 更新題目的遊玩次數與答對率
+    async fn record_round(&self, question_id: &str, answers: &[QuizAnswer]) {
+        if answers.is_empty() {
+            return;
+        }
+
+        let mut bank = self.bank.lock().await;
+        let Some(question) = bank.find_by_id_mut(question_id) else {
+            return;
+        };
+
+        let correct = answers.iter().filter(|a| a.correct).count() as f64;
+        let round_rate = correct / answers.len() as f64;
+        let feedback = &mut question.feedback;
+        feedback.total_plays += 1;
+        feedback.correct_rate +=
+            (round_rate - feedback.correct_rate) / feedback.total_plays as f64;
+
+        if let Err(e) = self.save(&mut bank) {
+            println!("[猜歌] ⚠️ 無法儲存題庫: {:?}", e);
+        }
+    }
+
+    /// 記錄一筆 👍/👎 評價，回傳題目目前的 `(likes, dislikes)`
+    async fn record_feedback(&self, log: FeedbackLog) -> Option<(u32, u32)> {
+        let counts = {
+            let mut bank = self.bank.lock().await;
+            let question = bank.find_by_id_mut(&log.quiz_id)?;
+            match log.feedback_type {
+                FeedbackType::Like => question.feedback.likes += 1,
+                FeedbackType::Dislike => question.feedback.dislikes += 1,
+            }
+            let counts = (question.feedback.likes, question.feedback.dislikes);
+
+            if let Err(e) = self.save(&mut bank) {
+                println!("[猜歌] ⚠️ 無法儲存題庫: {:?}", e);
+            }
+            counts
+        };
+
+        let mut logs = self.feedback_logs.lock().await;
+        logs.push(log);
+        if let Err(e) = self.save_feedback_logs(&logs) {
+            println!("[猜歌] ⚠️ 無法儲存評價紀錄: {:?}", e);
+        }
+
+        Some(counts)
+    }
+
+    /// 匯入題庫，`replace` 為 true 時取代整個題庫，否則以 id 合併
+    pub async fn import(&self, imported: QuizBank, replace: bool) -> Result<usize, Vec<(String, QuizProblem)>> {
+        let problems = imported.validate();
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+
+        let count = imported.quiz_bank.len();
+        let mut bank = self.bank.lock().await;
+        if replace {
+            bank.quiz_bank = imported.quiz_bank;
+        } else {
+            for question in imported.quiz_bank {
+                match bank.quiz_bank.iter_mut().find(|q| q.id == question.id) {
+                    Some(existing) => *existing = question,
+                    None => bank.quiz_bank.push(question),
+                }
+            }
+        }
+        bank.reindex();
+
+        if let Err(e) = self.save(&mut bank) {
+            println!("[猜歌] ⚠️ 無法儲存題庫: {:?}", e);
+        }
+        Ok(count)
+    }
+
+    pub async fn export(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&*self.bank.lock().await)
+    }
+
+    pub async fn has_track(&self, track_id: &str) -> bool {
+        self.bank.lock().await.find_by_track(track_id).is_some()
+    }
+
+    pub async fn question_count(&self) -> usize {
+        self.bank.lock().await.quiz_bank.len()
+    }
+
+    /// 新增題目，id 會自動產生
+    pub async fn add(&self, question: QuizQuestion) -> Result<String, Vec<QuizProblem>> {
+        let mut bank = self.bank.lock().await;
+        self.add_locked(&mut bank, question)
+    }
+
+    /// 快取自動產生的題目，同一首歌已有題目時直接回傳既有的 id
+    pub async fn add_generated(&self, question: QuizQuestion) -> Result<String, Vec<QuizProblem>> {
+        let mut bank = self.bank.lock().await;
+        if let Some(existing) = bank.find_by_track(&question.spotify_track_id) {
+            return Ok(existing.id.clone());
+        }
+        self.add_locked(&mut bank, question)
+    }
+
+    fn add_locked(&self, bank: &mut QuizBank, mut question: QuizQuestion) -> Result<String, Vec<QuizProblem>> {
+        question.id = bank.next_id();
+
+        let problems = question.validate();
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+
+        let id = question.id.clone();
+        bank.quiz_bank.push(question);
+        bank.reindex();
+
+        if let Err(e) = self.save(bank) {
+            println!("[猜歌] ⚠️ 無法儲存題庫: {:?}", e);
+        }
+        Ok(id)
+    }
+
+    /// 修改題目，修改後不符合格式時不會套用
+    pub async fn edit<F>(&self, id: &str, apply: F) -> Result<(), Vec<QuizProblem>>
+    where
+        F: FnOnce(&mut QuizQuestion),
+    {
+        let mut bank = self.bank.lock().await;
+        let Some(question) = bank.find_by_id_mut(id) else {
+            return Err(vec![QuizProblem::NotFound(id.to_string())]);
+        };
+
+        let mut edited = question.clone();
+        apply(&mut edited);
+        edited.id = id.to_string();
+
+        let problems = edited.validate();
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+
+        *question = edited;
+        bank.reindex();
+
+        if let Err(e) = self.save(&mut bank) {
+            println!("[猜歌] ⚠️ 無法儲存題庫: {:?}", e);
+        }
+        Ok(())
+    }
+
+    pub async fn remove(&self, id: &str) -> Option<QuizQuestion> {
+        let mut bank = self.bank.lock().await;
+        let index = bank.quiz_bank.iter().position(|q| q.id == id)?;
+        let removed = bank.quiz_bank.remove(index);
+        bank.reindex();
+
+        if let Err(e) = self.save(&mut bank) {
+            println!("[猜歌] ⚠️ 無法儲存題庫: {:?}", e);
+        }
+        Some(removed)
+    }
+
+    /// 品質報告：列出所有被標記的題目與原因，以及不符合格式的題目
+    pub async fn quality_report(&self) -> QualityReport {
+        let bank = self.bank.lock().await;
+        let flagged = bank
+            .playable()
+            .map(|q| (q.clone(), q.quality_flags()))
+            .filter(|(_, flags)| !flags.is_empty())
+            .collect();
+        let invalid = bank
+            .invalid_questions()
+            .map(|(q, problems)| (q.clone(), problems.clone()))
+            .collect();
+        QualityReport {
+            total: bank.quiz_bank.len(),
+            flagged,
+            invalid,
+        }
+    }
+
+    fn save(&self, bank: &mut QuizBank) -> std::io::Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        bank.last_updated = Utc::now();
+        let content = serde_json::to_string_pretty(bank)?;
+        write_atomically(path, content)
+    }
+
+    fn save_feedback_logs(&self, logs: &[FeedbackLog]) -> std::io::Result<()> {
+        let Some(ref path) = self.feedback_path else {
+            return Ok(());
+        };
+
+        let file = FeedbackLogFile {
+            version: BANK_VERSION.to_string(),
+            feedback_logs: logs.to_vec(),
+        };
+        let content = serde_json::to_string(&file)?;
+        write_atomically(path, content)
+    }
+}
+
+// 先寫入暫存檔再改名，避免寫到一半時損壞原檔案
+fn write_atomically(path: &Path, content: String) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)?;
+    std::fs::rename(tmp_path, path)
+}
+
+fn feedback_buttons(prefix: &str, disabled: bool) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}:like", prefix))
+            .label("👍")
+            .style(ButtonStyle::Secondary)
+            .disabled(disabled),
+        CreateButton::new(format!("{}:dislike", prefix))
+            .label("👎")
+            .style(ButtonStyle::Secondary)
+            .disabled(disabled),
+    ])]
+}
+
+// 一輪遊戲結束後，評價階段需要的資料
+struct FeedbackRound {
+    lang: Language,
+    guild_id: GuildId,
+    prefix: String,
+    question_id: String,
+    options: Vec<String>,
+    answers: Vec<QuizAnswer>,
+}
+
+/// 公布結果後收集玩家對題目的 👍/👎 評價，每人只能評價一次
+async fn collect_feedback(
+    ctx: serenity::all::Context,
+    quiz: std::sync::Arc<QuizState>,
+    mut message: Message,
+    round: FeedbackRound,
+) {
+    let FeedbackRound {
+        lang,
+        guild_id,
+        prefix,
+        question_id,
+        options,
+        answers,
+    } = round;
+    let mut voted = HashSet::new();
+    let mut collector = ComponentInteractionCollector::new(&ctx)
+        .message_id(message.id)
+        .timeout(FEEDBACK_TIME)
+        .stream();
+
+    while let Some(interaction) = collector.next().await {
+        let feedback_type = match interaction.data.custom_id.strip_prefix(&format!("{}:", prefix)) {
+            Some("like") => FeedbackType::Like,
+            Some("dislike") => FeedbackType::Dislike,
+            _ => continue,
+        };
+
+        let user_id = interaction.user.id;
+        let response = if !voted.insert(user_id) {
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(tr!(lang, "quiz-feedback-already-voted"))
+                    .ephemeral(true),
+            )
+        } else {
+            let answer = answers.iter().find(|a| a.user_id == user_id);
+            let log = FeedbackLog {
+                log_id: format!("fb_{}_{}", question_id, uuid::Uuid::new_v4().simple()),
+                quiz_id: question_id.clone(),
+                user_id,
+                guild_id,
+                feedback_type,
+                user_answer: answer.map(|a| options[a.choice].clone()),
+                is_correct: answer.map(|a| a.correct),
+                answer_time_ms: answer.map(|a| a.elapsed_ms),
+                timestamp: Utc::now(),
+            };
+
+            match quiz.record_feedback(log).await {
+                Some((likes, dislikes)) => CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(tr!(lang, "quiz-feedback-count", likes = likes, dislikes = dislikes)),
+                ),
+                None => CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(tr!(lang, "quiz-feedback-removed"))
+                        .ephemeral(true),
+                ),
+            }
+        };
+
+        if let Err(e) = interaction.create_response(&ctx, response).await {
+            println!("[猜歌] ⚠️ 無法回應按鈕互動: {:?}", e);
+        }
+    }
+
+    if let Err(e) = message
+        .edit(&ctx, EditMessage::new().components(feedback_buttons(&prefix, true)))
+        .await
+    {
+        println!("[猜歌] ⚠️ 無法停用評價按鈕: {:?}", e);
+    }
+}
+
+// 歌名或專輯名稱可能超過按鈕的長度上限，超過時截斷並加上 `…`
+fn button_label(label: &str, option: &str) -> String {
+    let text = format!("{}. {}", label, option);
+    if text.chars().count() <= MAX_BUTTON_LABEL {
+        return text;
+    }
+    let mut truncated: String = text.chars().take(MAX_BUTTON_LABEL - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn option_buttons(prefix: &str, options: &[String], reveal: Option<usize>) -> Vec<CreateActionRow> {
+    let buttons: Vec<CreateButton> = options
+        .iter()
+        .zip(OPTION_LABELS)
+        .enumerate()
+        .map(|(i, (option, label))| {
+            let style = match reveal {
+                Some(correct) if correct == i => ButtonStyle::Success,
+                Some(_) => ButtonStyle::Secondary,
+                None => ButtonStyle::Primary,
+            };
+            CreateButton::new(format!("{}:{}", prefix, i))
+                .label(button_label(label, option))
+                .style(style)
+                .disabled(reveal.is_some())
+        })
+        .collect();
+
+    // 每列兩個按鈕，排成 A/B、C/D
+    buttons
+        .chunks(2)
+        .map(|row| CreateActionRow::Buttons(row.to_vec()))
+        .collect()
+}
+
+fn question_embed(lang: Language, question: &QuizQuestion, matched: bool, deadline: DateTime<Utc>) -> CreateEmbed {
+    let mut description = String::new();
+    if !matched {
+        description.push_str(&tr!(lang, "quiz-random-question"));
+        description.push('\n');
+    }
+    // 猜歌名題型不能先顯示歌名
+    if question.quiz_type != QuizType::SongName {
+        description.push_str(&tr!(lang, "quiz-question-track", track = question.track_name.as_str()));
+        description.push('\n');
+    }
+    description.push_str(&format!(
+        "\n**{}**\n\n{}",
+        question.question_text(lang),
+        tr!(lang, "quiz-deadline", deadline = format!("<t:{}:R>", deadline.timestamp()))
+    ));
+
+    CreateEmbed::new()
+        .title(tr!(lang, "quiz-title"))
+        .description(description)
+        .colour(0x1DB954)
+}
+
+fn result_embed(
+    lang: Language,
+    question: &QuizQuestion,
+    options: &[String],
+    correct_index: usize,
+    answers: &[QuizAnswer],
+    points: &HashMap<UserId, u32>,
+) -> CreateEmbed {
+    let correct_count = answers.iter().filter(|a| a.correct).count();
+
+    let mut ranking: Vec<&QuizAnswer> = answers.iter().filter(|a| a.correct).collect();
+    ranking.sort_by_key(|a| a.elapsed_ms);
+    let ranking = if ranking.is_empty() {
+        tr!(lang, "quiz-nobody-correct")
+    } else {
+        ranking
+            .iter()
+            .take(10)
+            .enumerate()
+            .map(|(i, a)| {
+                let medal = match i {
+                    0 => "🥇".to_string(),
+                    1 => "🥈".to_string(),
+                    2 => "🥉".to_string(),
+                    _ => format!("`{}.`", i + 1),
+                };
+                tr!(
+                    lang,
+                    "quiz-ranking-entry",
+                    medal = medal,
+                    user = format!("<@{}>", a.user_id),
+                    seconds = format!("{:.1}", a.elapsed_ms as f64 / 1000.0),
+                    points = points.get(&a.user_id).copied().unwrap_or(0)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    CreateEmbed::new()
+        .title(tr!(lang, "quiz-result-title"))
+        .description(format!(
+            "**{}**\n\n{}",
+            question.question_text(lang),
+            tr!(
+                lang,
+                "quiz-result-answer",
+                label = OPTION_LABELS[correct_index],
+                answer = options[correct_index].as_str(),
+                track = question.track_name.as_str(),
+                artist = question.artist.as_str()
+            )
+        ))
+        .field(tr!(lang, "quiz-result-correct"), correct_count.to_string(), true)
+        .field(tr!(lang, "quiz-result-wrong"), (answers.len() - correct_count).to_string(), true)
+        .field(tr!(lang, "quiz-result-ranking"), ranking, false)
+        .colour(0x1DB954)
+}
+
+/// 根據目前播放的歌曲開始一輪猜歌遊戲
+#[poise::command(slash_command, guild_only)]
+pub async fn quiz(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let lang = i18n::language(ctx).await;
+
+    let Some(track_id) = ctx.data().now_playing.lock().await.clone() else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(tr!(lang, "quiz-nothing-playing"))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let in_voice = ctx
+        .guild()
+        .and_then(|guild| guild.voice_states.get(&ctx.author().id).and_then(|s| s.channel_id))
+        .is_some();
+    if !in_voice {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(tr!(lang, "quiz-join-voice"))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let quiz = ctx.data().quiz.clone();
+    if !quiz.active_games.lock().await.insert(guild_id) {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(tr!(lang, "quiz-already-running"))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // 題庫中沒有目前的歌時先自動出題，可能需要幾秒鐘
+    if let Err(e) = ctx.defer().await {
+        quiz.active_games.lock().await.remove(&guild_id);
+        return Err(e.into());
+    }
+    ensure_question(ctx.data(), &track_id).await;
+
+    let result = run_round(ctx, lang, guild_id, &quiz, &track_id).await;
+    quiz.active_games.lock().await.remove(&guild_id);
+    result
+}
+
+async fn run_round(
+    ctx: PoiseContext<'_>,
+    lang: Language,
+    guild_id: GuildId,
+    quiz: &QuizState,
+    track_id: &str,
+) -> Result<(), Error> {
+    let Some(question) = quiz.pick_question(track_id).await else {
+        ctx.say(tr!(lang, "quiz-bank-empty")).await?;
+        return Ok(());
+    };
+
+    // 選項隨機排序
+    let mut options: Vec<String> = question.options.iter().take(OPTION_LABELS.len()).cloned().collect();
+    options.shuffle(&mut rand::thread_rng());
+    let Some(correct_index) = options.iter().position(|o| *o == question.correct_answer) else {
+        println!("[猜歌] ⚠️ 題目 {} 的選項中沒有正確答案", question.id);
+        ctx.say(tr!(lang, "quiz-invalid-question")).await?;
+        return Ok(());
+    };
+
+    let matched = question.spotify_track_id == track_id;
+    let deadline = Utc::now() + ANSWER_TIME;
+    let prefix = format!("quiz:{}", ctx.id());
+
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .embed(question_embed(lang, &question, matched, deadline))
+                .components(option_buttons(&prefix, &options, None)),
+        )
+        .await?;
+    let message_id = reply.message().await?.id;
+
+    let started = Instant::now();
+    let mut answers: Vec<QuizAnswer> = Vec::new();
+    let mut collector = ComponentInteractionCollector::new(ctx.serenity_context())
+        .message_id(message_id)
+        .timeout(ANSWER_TIME)
+        .stream();
+
+    while let Some(interaction) = collector.next().await {
+        let Some(choice) = interaction
+            .data
+            .custom_id
+            .strip_prefix(&format!("{}:", prefix))
+            .and_then(|i| i.parse::<usize>().ok())
+            .filter(|&i| i < options.len())
+        else {
+            continue;
+        };
+
+        let user_id = interaction.user.id;
+        let content = if answers.iter().any(|a| a.user_id == user_id) {
+            tr!(lang, "quiz-already-answered")
+        } else {
+            answers.push(QuizAnswer {
+                user_id,
+                choice,
+                correct: choice == correct_index,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            });
+            tr!(
+                lang,
+                "quiz-answer-received",
+                label = OPTION_LABELS[choice],
+                answer = options[choice].as_str()
+            )
+        };
+
+        if let Err(e) = interaction
+            .create_response(
+                ctx.serenity_context(),
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(content)
+                        .ephemeral(true),
+                ),
+            )
+            .await
+        {
+            println!("[猜歌] ⚠️ 無法回應按鈕互動: {:?}", e);
+        }
+    }
+
+    quiz.record_round(&question.id, &answers).await;
+    let points = ctx
+        .data()
+        .scores
+        .lock()
+        .await
+        .record_round(guild_id, &question.id, &answers);
+
+    // 時間到：停用按鈕並顯示結果
+    reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .embed(result_embed(lang, &question, &options, correct_index, &answers, &points))
+                .components(option_buttons(&prefix, &options, Some(correct_index))),
+        )
+        .await?;
+
+    // 另外發送評價訊息，在背景收集 👍/👎
+    let feedback_message = ctx
+        .channel_id()
+        .send_message(
+            ctx,
+            CreateMessage::new()
+                .content(tr!(lang, "quiz-feedback-prompt"))
+                .components(feedback_buttons(&prefix, false)),
+        )
+        .await?;
+    tokio::spawn(collect_feedback(
+        ctx.serenity_context().clone(),
+        ctx.data().quiz.clone(),
+        feedback_message,
+        FeedbackRound {
+            lang,
+            guild_id,
+            prefix,
+            question_id: question.id.clone(),
+            options,
+            answers,
+        },
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(id: &str, track_id: &str) -> QuizQuestion {
+        QuizQuestion {
+            id: id.to_string(),
+            spotify_track_id: track_id.to_string(),
+            track_name: "紅蓮の弓矢".to_string(),
+            artist: "Linked Horizon".to_string(),
+            quiz_type: QuizType::Anime,
+            question: "這首歌出自哪部動畫？".to_string(),
+            correct_answer: "進撃の巨人".to_string(),
+            options: ["進撃の巨人", "鬼滅の刃", "呪術廻戦", "SPY×FAMILY"].map(String::from).to_vec(),
+            difficulty: Difficulty::Easy,
+            tags: Vec::new(),
+            feedback: QuizFeedback::default(),
+            source: QuizSource::Manual,
+            created_at: Utc::now(),
+            ai_confidence: None,
+        }
+    }
+
+    // 一道正常的題目加上三道壞掉的題目
+    fn bank_with_invalid_questions() -> QuizBank {
+        let mut bad_answer = question("quiz_002", "track_2");
+        bad_answer.correct_answer = "ONE PIECE".to_string();
+        let mut duplicate_options = question("quiz_003", "track_3");
+        duplicate_options.options[1] = "進撃の巨人".to_string();
+        let mut empty_question = question("quiz_004", "track_4");
+        empty_question.question = "  ".to_string();
+
+        QuizBank {
+            quiz_bank: vec![question("quiz_001", "track_1"), bad_answer, duplicate_options, empty_question],
+            ..QuizBank::default()
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quiz-test-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn generated_question_text_follows_the_language_until_edited() {
+        let mut generated = question("quiz_001", "track_1");
+        generated.source = QuizSource::Metadata;
+        generated.question = tr!(Language::default(), QuizType::Anime.generated_question_id());
+        assert_eq!(
+            generated.question_text(Language::En),
+            tr!(Language::En, QuizType::Anime.generated_question_id())
+        );
+
+        generated.quiz_type = QuizType::Artist;
+        assert_eq!(
+            generated.question_text(Language::En),
+            tr!(Language::En, QuizType::Artist.generated_question_id())
+        );
+
+        generated.question = "Which anime opens with this song?".to_string();
+        assert_eq!(generated.question_text(Language::ZhTw), "Which anime opens with this song?");
+    }
+
+    #[test]
+    fn default_bank_uses_the_supported_version() {
+        assert_eq!(QuizBank::default().version, BANK_VERSION);
+        assert!(QuizBank::default().validate().is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalid_questions_are_reported_but_never_played() {
+        let dir = temp_dir("invalid");
+        let content = serde_json::to_string(&bank_with_invalid_questions()).unwrap();
+        std::fs::write(dir.join(QUIZ_BANK_FILE), content).unwrap();
+        let quiz = QuizState::load(dir.to_str());
+
+        let report = quiz.quality_report().await;
+        let invalid: Vec<(&str, &[QuizProblem])> = report
+            .invalid
+            .iter()
+            .map(|(q, problems)| (q.id.as_str(), problems.as_slice()))
+            .collect();
+        assert_eq!(report.total, 4);
+        assert_eq!(
+            invalid,
+            [
+                ("quiz_002", &[QuizProblem::AnswerNotInOptions][..]),
+                ("quiz_003", &[QuizProblem::DuplicateOption][..]),
+                ("quiz_004", &[QuizProblem::EmptyField("question")][..]),
+            ]
+        );
+
+        // 指定壞掉題目的曲目時改為隨機出一道正常的題目
+        for track_id in ["track_2", "track_3", "track_4"] {
+            assert!(!quiz.has_track(track_id).await);
+            assert_eq!(quiz.pick_question(track_id).await.unwrap().id, "quiz_001");
+        }
+
+        // 其他修改寫回檔案時仍保留壞掉的題目
+        quiz.add(question("", "track_5")).await.unwrap();
+        let content = std::fs::read_to_string(dir.join(QUIZ_BANK_FILE)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        let saved: QuizBank = serde_json::from_str(&content).unwrap();
+        assert_eq!(saved.quiz_bank.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn fixing_an_invalid_question_makes_it_playable() {
+        let quiz = QuizState::load(None);
+        quiz.bank.lock().await.quiz_bank = bank_with_invalid_questions().quiz_bank;
+        quiz.bank.lock().await.reindex();
+        assert!(!quiz.has_track("track_2").await);
+
+        quiz.edit("quiz_002", |q| q.correct_answer = "鬼滅の刃".to_string()).await.unwrap();
+
+        assert!(quiz.has_track("track_2").await);
+        assert_eq!(quiz.quality_report().await.invalid.len(), 2);
+    }
+
+    #[test]
+    fn duplicate_ids_keep_the_first_question_playable() {
+        let mut bank = QuizBank {
+            quiz_bank: vec![question("quiz_001", "track_1"), question("quiz_001", "track_2")],
+            ..QuizBank::default()
+        };
+        bank.reindex();
+
+        assert!(bank.find_by_track("track_1").is_some());
+        assert!(bank.find_by_track("track_2").is_none());
+        assert_eq!(bank.invalid.get(&1), Some(&vec![QuizProblem::DuplicateId]));
+    }
+
+    #[test]
+    fn short_button_labels_are_unchanged() {
+        assert_eq!(button_label("A", "紅蓮の弓矢"), "A. 紅蓮の弓矢");
+    }
+
+    #[test]
+    fn long_button_labels_are_truncated_at_a_char_boundary() {
+        let option = "進撃の巨人".repeat(30);
+        let label = button_label("B", &option);
+        assert_eq!(label.chars().count(), MAX_BUTTON_LABEL);
+        assert!(label.starts_with("B. 進撃の巨人"));
+        assert!(label.ends_with('…'));
+    }
+}
@@ -0,0 +1,234 @@
+///lib/quiz_admin.rs
+use chrono::Utc;
+use serenity::all::{Attachment, CreateAttachment, CreateEmbed};
+
+use crate::lib::i18n::{self, tr};
+use crate::lib::permissions::{Capability, OwnerOnly};
+use crate::lib::quiz::{Difficulty, QuizBank, QuizFeedback, QuizProblem, QuizQuestion, QuizSource, QuizType};
+use crate::lib::settings::Language;
+use crate::{Error, PoiseContext, say_ephemeral};
+
+// 品質報告最多列出的題目數
+const MAX_REPORT_ENTRIES: usize = 20;
+
+fn format_problems(lang: Language, problems: &[QuizProblem]) -> String {
+    problems
+        .iter()
+        .take(10)
+        .map(|p| format!("• {}", p.message(lang)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 從 JSON 檔案匯入題庫（擁有者）
+#[poise::command(slash_command, custom_data = OwnerOnly)]
+pub async fn quiz_import(
+    ctx: PoiseContext<'_>,
+    #[description = "符合 quiz_bank.json 格式的檔案"] file: Attachment,
+    #[description = "取代整個題庫（預設以 id 合併）"] replace: Option<bool>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let lang = i18n::language(ctx).await;
+
+    let content = file.download().await?;
+    let bank = match serde_json::from_slice::<QuizBank>(&content) {
+        Ok(bank) => bank,
+        Err(e) => {
+            return say_ephemeral(ctx, tr!(lang, "quiz-import-parse-failed", error = e.to_string())).await;
+        }
+    };
+
+    match ctx.data().quiz.import(bank, replace.unwrap_or(false)).await {
+        Ok(count) => say_ephemeral(ctx, tr!(lang, "quiz-imported", count = count)).await,
+        Err(problems) => {
+            let problems: Vec<String> = problems
+                .iter()
+                .take(10)
+                .map(|(id, problem)| format!("• {}: {}", id, problem.message(lang)))
+                .collect();
+            say_ephemeral(ctx, tr!(lang, "quiz-import-invalid", problems = problems.join("\n"))).await
+        }
+    }
+}
+
+/// 匯出目前的題庫（管理員）
+#[poise::command(slash_command, custom_data = Capability::QuizAdmin)]
+pub async fn quiz_export(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let content = ctx.data().quiz.export().await?;
+    ctx.send(
+        poise::CreateReply::default()
+            .content(tr!(lang, "quiz-export-done"))
+            .attachment(CreateAttachment::bytes(content, "quiz_bank.json"))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 新增一道題目（擁有者）
+#[poise::command(slash_command, custom_data = OwnerOnly)]
+pub async fn quiz_add(
+    ctx: PoiseContext<'_>,
+    #[description = "Spotify Track ID"] spotify_track_id: String,
+    #[description = "歌名"] track_name: String,
+    #[description = "演唱者"] artist: String,
+    #[description = "題型"] quiz_type: QuizType,
+    #[description = "題目"] question: String,
+    #[description = "正確答案"] correct_answer: String,
+    #[description = "干擾選項 1"] wrong_1: String,
+    #[description = "干擾選項 2"] wrong_2: String,
+    #[description = "干擾選項 3"] wrong_3: String,
+    #[description = "難度（預設普通）"] difficulty: Option<Difficulty>,
+) -> Result<(), Error> {
+    let question = QuizQuestion {
+        id: String::new(),
+        spotify_track_id,
+        track_name,
+        artist,
+        quiz_type,
+        question,
+        options: vec![correct_answer.clone(), wrong_1, wrong_2, wrong_3],
+        correct_answer,
+        difficulty: difficulty.unwrap_or(Difficulty::Medium),
+        tags: Vec::new(),
+        feedback: QuizFeedback::default(),
+        source: QuizSource::Manual,
+        created_at: Utc::now(),
+        ai_confidence: None,
+    };
+
+    let lang = i18n::language(ctx).await;
+    match ctx.data().quiz.add(question).await {
+        Ok(id) => say_ephemeral(ctx, tr!(lang, "quiz-added", id = id)).await,
+        Err(problems) => {
+            say_ephemeral(ctx, tr!(lang, "quiz-add-invalid", problems = format_problems(lang, &problems))).await
+        }
+    }
+}
+
+/// 修改題目內容（擁有者）
+#[poise::command(slash_command, custom_data = OwnerOnly)]
+pub async fn quiz_edit(
+    ctx: PoiseContext<'_>,
+    #[description = "題目 id"] id: String,
+    #[description = "新的題目"] question: Option<String>,
+    #[description = "新的正確答案"] correct_answer: Option<String>,
+    #[description = "新的四個選項，以 | 分隔"] options: Option<String>,
+    #[description = "新的題型"] quiz_type: Option<QuizType>,
+    #[description = "新的難度"] difficulty: Option<Difficulty>,
+) -> Result<(), Error> {
+    let result = ctx
+        .data()
+        .quiz
+        .edit(&id, |q| {
+            if let Some(question) = question {
+                q.question = question;
+            }
+            if let Some(correct_answer) = correct_answer {
+                q.correct_answer = correct_answer;
+            }
+            if let Some(options) = options {
+                q.options = options.split('|').map(|o| o.trim().to_string()).collect();
+            }
+            if let Some(quiz_type) = quiz_type {
+                q.quiz_type = quiz_type;
+            }
+            if let Some(difficulty) = difficulty {
+                q.difficulty = difficulty;
+            }
+        })
+        .await;
+
+    let lang = i18n::language(ctx).await;
+    match result {
+        Ok(()) => say_ephemeral(ctx, tr!(lang, "quiz-edited", id = id)).await,
+        Err(problems) => {
+            say_ephemeral(ctx, tr!(lang, "quiz-edit-failed", problems = format_problems(lang, &problems))).await
+        }
+    }
+}
+
+/// 從題庫移除題目（擁有者）
+#[poise::command(slash_command, custom_data = OwnerOnly)]
+pub async fn quiz_remove(
+    ctx: PoiseContext<'_>,
+    #[description = "題目 id"] id: String,
+) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    match ctx.data().quiz.remove(&id).await {
+        Some(question) => {
+            say_ephemeral(
+                ctx,
+                tr!(lang, "quiz-removed", id = question.id, track = question.track_name),
+            )
+            .await
+        }
+        None => say_ephemeral(ctx, tr!(lang, "quiz-not-found", id = id)).await,
+    }
+}
+
+/// 產生題目品質報告（管理員）
+#[poise::command(slash_command, custom_data = Capability::QuizAdmin)]
+pub async fn quiz_report(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let lang = i18n::language(ctx).await;
+    let report = ctx.data().quiz.quality_report().await;
+
+    // 不會出題的題目排在前面，管理員需要先修正
+    let invalid = report.invalid.iter().map(|(q, problems)| {
+        tr!(
+            lang,
+            "quiz-report-invalid-entry",
+            id = q.id.as_str(),
+            track = q.track_name.as_str(),
+            problems = problems
+                .iter()
+                .map(|problem| problem.message(lang))
+                .collect::<Vec<_>>()
+                .join(&tr!(lang, "quiz-report-flag-separator"))
+        )
+    });
+    let flagged = report.flagged.iter().map(|(q, flags)| {
+        tr!(
+            lang,
+            "quiz-report-entry",
+            id = q.id.as_str(),
+            track = q.track_name.as_str(),
+            likes = q.feedback.likes,
+            dislikes = q.feedback.dislikes,
+            plays = q.feedback.total_plays,
+            flags = flags
+                .iter()
+                .map(|flag| flag.message(lang))
+                .collect::<Vec<_>>()
+                .join(&tr!(lang, "quiz-report-flag-separator"))
+        )
+    });
+
+    let entries = report.invalid.len() + report.flagged.len();
+    let description = if entries == 0 {
+        tr!(lang, "quiz-report-clean")
+    } else {
+        let mut lines: Vec<String> = invalid.chain(flagged).take(MAX_REPORT_ENTRIES).collect();
+        if entries > MAX_REPORT_ENTRIES {
+            lines.push(tr!(lang, "quiz-report-more", count = entries - MAX_REPORT_ENTRIES));
+        }
+        lines.join("\n")
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title(tr!(lang, "quiz-report-title"))
+                    .description(description)
+                    .field(tr!(lang, "quiz-report-total"), report.total.to_string(), true)
+                    .field(tr!(lang, "quiz-report-flagged"), report.flagged.len().to_string(), true)
+                    .field(tr!(lang, "quiz-report-invalid"), report.invalid.len().to_string(), true)
+                    .colour(0x1DB954),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,310 @@
+///lib/quiz_generator.rs
+use std::collections::HashSet;
+
+use chrono::Utc;
+use librespot::core::{session::Session, spotify_id::SpotifyId};
+use rand::seq::SliceRandom;
+use serenity::async_trait;
+
+use crate::lib::history::HistoryEntry;
+use crate::lib::i18n::tr;
+use crate::lib::metadata::{spotify_id, MetadataCache};
+use crate::lib::quiz::{Difficulty, QuizFeedback, QuizQuestion, QuizSource, QuizType};
+use crate::lib::settings::Language;
+use crate::Data;
+
+// 收集同專輯曲目時最多查詢的曲目數，避免一次發出太多 Metadata 請求
+const MAX_ALBUM_TRACKS: usize = 8;
+// 從播放歷史中取用的最近筆數
+const HISTORY_LOOKBACK: usize = 200;
+const DISTRACTOR_COUNT: usize = 3;
+
+/// 出題需要的曲目資訊與干擾選項來源
+#[derive(Clone, Debug, Default)]
+pub struct TrackInfo {
+    pub track_id: String,
+    pub track_name: String,
+    pub artists: Vec<String>,
+    pub album_name: String,
+    // 同專輯的其他曲目與演唱者
+    pub album_tracks: Vec<String>,
+    pub album_artists: Vec<String>,
+    // 播放歷史中的其他曲目與演唱者
+    pub history_tracks: Vec<String>,
+    pub history_artists: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum GenerateError {
+    NotEnoughDistractors,
+    Metadata(String),
+}
+
+impl std::fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenerateError::NotEnoughDistractors => write!(f, "沒有足夠的干擾選項"),
+            GenerateError::Metadata(e) => write!(f, "無法獲取 Metadata: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+/// 題目產生器，之後的外部 LLM API 也實作這個 trait
+#[async_trait]
+pub trait QuestionGenerator: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn generate(&self, track: &TrackInfo) -> Result<QuizQuestion, GenerateError>;
+}
+
+/// 離線出題：用 Spotify Metadata 與播放歷史組出干擾選項
+pub struct MetadataQuestionGenerator;
+
+impl MetadataQuestionGenerator {
+    // 從多個來源依序挑出不重複、且不等於正確答案的干擾選項
+    fn pick_distractors(correct: &str, sources: &[&[String]]) -> Option<Vec<String>> {
+        let mut seen: HashSet<String> = HashSet::from([correct.to_lowercase()]);
+        let mut distractors = Vec::with_capacity(DISTRACTOR_COUNT);
+
+        for source in sources {
+            let mut candidates: Vec<&String> = source.iter().collect();
+            candidates.shuffle(&mut rand::thread_rng());
+
+            for candidate in candidates {
+                if distractors.len() == DISTRACTOR_COUNT {
+                    return Some(distractors);
+                }
+                if !candidate.trim().is_empty() && seen.insert(candidate.to_lowercase()) {
+                    distractors.push(candidate.clone());
+                }
+            }
+        }
+
+        (distractors.len() == DISTRACTOR_COUNT).then_some(distractors)
+    }
+
+    fn build(track: &TrackInfo, quiz_type: QuizType, correct: String, distractors: Vec<String>) -> QuizQuestion {
+        // 題庫中保存預設語言的文字，顯示時依照題型翻譯（見 `QuizQuestion::question_text`）
+        let question = tr!(Language::default(), quiz_type.generated_question_id());
+
+        let mut options = distractors;
+        options.push(correct.clone());
+
+        QuizQuestion {
+            id: String::new(),
+            spotify_track_id: track.track_id.clone(),
+            track_name: track.track_name.clone(),
+            artist: track.artists.join(", "),
+            quiz_type,
+            question,
+            correct_answer: correct,
+            options,
+            difficulty: Difficulty::Medium,
+            tags: vec!["generated".to_string()],
+            feedback: QuizFeedback::default(),
+            source: QuizSource::Metadata,
+            created_at: Utc::now(),
+            ai_confidence: None,
+        }
+    }
+}
+
+#[async_trait]
+impl QuestionGenerator for MetadataQuestionGenerator {
+    fn name(&self) -> &'static str {
+        "metadata"
+    }
+
+    async fn generate(&self, track: &TrackInfo) -> Result<QuizQuestion, GenerateError> {
+        // 優先出猜歌手題，干擾選項不足時改出猜歌名題
+        if let Some(artist) = track.artists.first() {
+            let distractors = Self::pick_distractors(
+                artist,
+                &[&track.album_artists, &track.history_artists],
+            );
+            if let Some(distractors) = distractors {
+                return Ok(Self::build(track, QuizType::Artist, artist.clone(), distractors));
+            }
+        }
+
+        let distractors = Self::pick_distractors(
+            &track.track_name,
+            &[&track.album_tracks, &track.history_tracks],
+        )
+        .ok_or(GenerateError::NotEnoughDistractors)?;
+
+        Ok(Self::build(track, QuizType::SongName, track.track_name.clone(), distractors))
+    }
+}
+
+/// 透過 Metadata 與播放歷史收集出題需要的資訊
+pub async fn collect_track_info(
+    metadata: &MetadataCache,
+    session: &Session,
+    track_id: &SpotifyId,
+    history: &[HistoryEntry],
+) -> Result<TrackInfo, GenerateError> {
+    let track = metadata
+        .track(session, track_id)
+        .await
+        .map_err(|e| GenerateError::Metadata(format!("{:?}", e)))?;
+    let base62 = track_id.to_base62().unwrap_or_default();
+
+    let mut info = TrackInfo {
+        track_id: base62.clone(),
+        track_name: track.name.clone(),
+        artists: metadata.artist_names(session, &track).await,
+        ..Default::default()
+    };
+
+    // 同專輯的其他曲目（查不到專輯時仍可用播放歷史出題）
+    let album = match spotify_id("album", &track.album_id) {
+        Some(album_id) => metadata.album(session, &album_id).await.ok(),
+        None => None,
+    };
+    if let Some(album) = album {
+        info.album_name = album.name.clone();
+        for album_track_id in album
+            .track_ids
+            .iter()
+            .filter(|id| **id != base62)
+            .filter_map(|id| spotify_id("track", id))
+            .take(MAX_ALBUM_TRACKS)
+        {
+            if let Ok(album_track) = metadata.track(session, &album_track_id).await {
+                info.album_artists.extend(metadata.artist_names(session, &album_track).await);
+                info.album_tracks.push(album_track.name);
+            }
+        }
+    }
+
+    for entry in history.iter().rev().take(HISTORY_LOOKBACK) {
+        if entry.track_id == base62 {
+            continue;
+        }
+        info.history_tracks.push(entry.title.clone());
+        info.history_artists.extend(entry.artists.iter().cloned());
+    }
+
+    Ok(info)
+}
+
+/// 題庫中沒有這首歌時自動出題並寫回題庫，每首歌只會產生一次
+pub async fn ensure_question(data: &Data, track_id: &str) {
+    if data.quiz.has_track(track_id).await {
+        return;
+    }
+
+    let Some(track_spotify_id) = spotify_id("track", track_id) else {
+        return;
+    };
+
+    let session = data.player.lock().await.session.clone();
+    let history = data.history.lock().await.entries().to_vec();
+
+    let generator = &data.quiz_generator;
+    let result = match collect_track_info(&data.metadata, &session, &track_spotify_id, &history).await {
+        Ok(info) => generator.generate(&info).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(question) => match data.quiz.add_generated(question).await {
+            Ok(id) => println!("[猜歌] ✓ {} 已為 {} 產生題目 {}", generator.name(), track_id, id),
+            Err(problems) => println!("[猜歌] ⚠️ 產生的題目不符合格式: {:?}", problems),
+        },
+        Err(e) => println!("[猜歌] ⚠️ {} 無法為 {} 出題: {}", generator.name(), track_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::quiz::QuizState;
+
+    /// 測試用的產生器，永遠回傳固定的題目
+    struct MockQuestionGenerator;
+
+    #[async_trait]
+    impl QuestionGenerator for MockQuestionGenerator {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        async fn generate(&self, track: &TrackInfo) -> Result<QuizQuestion, GenerateError> {
+            Ok(MetadataQuestionGenerator::build(
+                track,
+                QuizType::Artist,
+                "Correct".to_string(),
+                vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            ))
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn track_info() -> TrackInfo {
+        TrackInfo {
+            track_id: "3eR23VReFzcdmS7TYCrhCe".to_string(),
+            track_name: "紅蓮の弓矢".to_string(),
+            artists: strings(&["Linked Horizon"]),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn artist_question_uses_album_then_history() {
+        let info = TrackInfo {
+            album_artists: strings(&["Linked Horizon", "Revo"]),
+            history_artists: strings(&["LiSA", "Aimer", "linked horizon"]),
+            ..track_info()
+        };
+
+        let question = MetadataQuestionGenerator.generate(&info).await.unwrap();
+
+        assert_eq!(question.quiz_type, QuizType::Artist);
+        assert_eq!(question.correct_answer, "Linked Horizon");
+        assert!(question.options.contains(&"Revo".to_string()));
+        assert!(question.validate().is_empty(), "{:?}", question.validate());
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_song_name_question() {
+        let info = TrackInfo {
+            album_tracks: strings(&["自由の翼", "紅蓮の弓矢"]),
+            history_tracks: strings(&["Lemon", "夜に駆ける"]),
+            history_artists: strings(&["米津玄師"]),
+            ..track_info()
+        };
+
+        let question = MetadataQuestionGenerator.generate(&info).await.unwrap();
+
+        assert_eq!(question.quiz_type, QuizType::SongName);
+        assert_eq!(question.correct_answer, "紅蓮の弓矢");
+        assert!(question.validate().is_empty(), "{:?}", question.validate());
+    }
+
+    #[tokio::test]
+    async fn not_enough_distractors() {
+        let result = MetadataQuestionGenerator.generate(&track_info()).await;
+        assert!(matches!(result, Err(GenerateError::NotEnoughDistractors)));
+    }
+
+    #[tokio::test]
+    async fn generated_question_is_cached_once() {
+        let quiz = QuizState::load(None);
+        let info = track_info();
+
+        let first = MockQuestionGenerator.generate(&info).await.unwrap();
+        let id = quiz.add_generated(first).await.unwrap();
+        assert!(quiz.has_track(&info.track_id).await);
+
+        let second = MockQuestionGenerator.generate(&info).await.unwrap();
+        assert_eq!(quiz.add_generated(second).await.unwrap(), id);
+        assert_eq!(quiz.question_count().await, 1);
+    }
+}
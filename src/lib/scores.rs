@@ -0,0 +1,377 @@
+///lib/scores.rs
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::all::{CreateEmbed, GuildId, User, UserId};
+
+use crate::lib::i18n::{self, tr};
+use crate::lib::permissions::Capability;
+use crate::lib::quiz::{QuizAnswer, ANSWER_TIME};
+use crate::lib::storage::backup;
+use crate::{Error, PoiseContext};
+
+const USER_STATS_FILE: &str = "user_stats.json";
+// 每位使用者保留的最近作答紀錄筆數
+const MAX_RECENT_ANSWERS: usize = 20;
+
+const BASE_POINTS: u32 = 100;
+const MAX_SPEED_BONUS: u32 = 100;
+const STREAK_BONUS: u32 = 10;
+const MAX_STREAK_BONUS: u32 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AnswerRecord {
+    pub quiz_id: String,
+    pub is_correct: bool,
+    pub answer_time_ms: u64,
+    pub points: u32,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 單一使用者在某個伺服器的統計，欄位對應 `todolist.md` 的 `user_stats.json`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UserStats {
+    pub total_games: u32,
+    pub correct_answers: u32,
+    pub wrong_answers: u32,
+    pub accuracy_rate: f64,
+    pub average_answer_time_ms: u64,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub total_points: u64,
+    pub last_played: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub recent_answers: VecDeque<AnswerRecord>,
+}
+
+impl UserStats {
+    fn record(&mut self, quiz_id: &str, answer: &QuizAnswer) -> u32 {
+        // 平均作答時間以累計方式更新
+        let total_time = self.average_answer_time_ms * self.total_games as u64 + answer.elapsed_ms;
+        self.total_games += 1;
+        self.average_answer_time_ms = total_time / self.total_games as u64;
+
+        let points = if answer.correct {
+            self.correct_answers += 1;
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+            points_for(answer.elapsed_ms, self.current_streak)
+        } else {
+            self.wrong_answers += 1;
+            self.current_streak = 0;
+            0
+        };
+
+        self.accuracy_rate = self.correct_answers as f64 / self.total_games as f64;
+        self.total_points += points as u64;
+        self.last_played = Some(Utc::now());
+
+        self.recent_answers.push_back(AnswerRecord {
+            quiz_id: quiz_id.to_string(),
+            is_correct: answer.correct,
+            answer_time_ms: answer.elapsed_ms,
+            points,
+            timestamp: Utc::now(),
+        });
+        while self.recent_answers.len() > MAX_RECENT_ANSWERS {
+            self.recent_answers.pop_front();
+        }
+
+        points
+    }
+}
+
+/// 答對的基本分 + 速度加分（越快越高）+ 連勝獎勵
+pub fn points_for(elapsed_ms: u64, streak: u32) -> u32 {
+    let limit_ms = ANSWER_TIME.as_millis() as u64;
+    let remaining = limit_ms.saturating_sub(elapsed_ms);
+    let speed_bonus = (remaining * MAX_SPEED_BONUS as u64 / limit_ms) as u32;
+    let streak_bonus = streak.saturating_sub(1).saturating_mul(STREAK_BONUS).min(MAX_STREAK_BONUS);
+
+    BASE_POINTS + speed_bonus + streak_bonus
+}
+
+#[derive(Serialize, Deserialize)]
+struct UserStatsFile {
+    version: String,
+    guilds: HashMap<GuildId, HashMap<UserId, UserStats>>,
+}
+
+pub struct ScoreStore {
+    path: Option<PathBuf>,
+    guilds: HashMap<GuildId, HashMap<UserId, UserStats>>,
+}
+
+impl ScoreStore {
+    /// 從 `cache_dir` 載入分數，沒有快取目錄時只保存在記憶體中
+    pub fn load(cache_dir: Option<&str>) -> ScoreStore {
+        let path = cache_dir.map(|dir| Path::new(dir).join(USER_STATS_FILE));
+
+        let guilds = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => match serde_json::from_str::<UserStatsFile>(&content) {
+                Ok(file) => file.guilds,
+                Err(e) => {
+                    println!("[計分] ⚠️ 無法解析 {}: {:?}", USER_STATS_FILE, e);
+                    backup(path.as_deref(), "corrupt");
+                    HashMap::new()
+                }
+            },
+            _ => HashMap::new(),
+        };
+
+        ScoreStore { path, guilds }
+    }
+
+    /// 記錄一輪遊戲的作答，回傳每位使用者獲得的分數
+    pub fn record_round(
+        &mut self,
+        guild_id: GuildId,
+        quiz_id: &str,
+        answers: &[QuizAnswer],
+    ) -> HashMap<UserId, u32> {
+        if answers.is_empty() {
+            return HashMap::new();
+        }
+
+        let guild = self.guilds.entry(guild_id).or_default();
+        let points = answers
+            .iter()
+            .map(|answer| {
+                let stats = guild.entry(answer.user_id).or_default();
+                (answer.user_id, stats.record(quiz_id, answer))
+            })
+            .collect();
+
+        if let Err(e) = self.save() {
+            println!("[計分] ⚠️ 無法儲存分數: {:?}", e);
+        }
+        points
+    }
+
+    pub fn user(&self, guild_id: GuildId, user_id: UserId) -> Option<&UserStats> {
+        self.guilds.get(&guild_id)?.get(&user_id)
+    }
+
+    /// 依總分排序的排行榜
+    pub fn leaderboard(&self, guild_id: GuildId, n: usize) -> Vec<(UserId, &UserStats)> {
+        let Some(guild) = self.guilds.get(&guild_id) else {
+            return Vec::new();
+        };
+
+        let mut ranking: Vec<(UserId, &UserStats)> = guild.iter().map(|(id, s)| (*id, s)).collect();
+        ranking.sort_by(|a, b| b.1.total_points.cmp(&a.1.total_points));
+        ranking.truncate(n);
+        ranking
+    }
+
+    pub fn export(&self, guild_id: GuildId) -> serde_json::Result<String> {
+        let empty = HashMap::new();
+        serde_json::to_string_pretty(self.guilds.get(&guild_id).unwrap_or(&empty))
+    }
+
+    /// 重設整個伺服器或單一使用者的分數
+    pub fn reset(&mut self, guild_id: GuildId, user_id: Option<UserId>) -> std::io::Result<()> {
+        match user_id {
+            Some(user_id) => {
+                if let Some(guild) = self.guilds.get_mut(&guild_id) {
+                    guild.remove(&user_id);
+                }
+            }
+            None => {
+                self.guilds.remove(&guild_id);
+            }
+        }
+        self.save()
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        let file = UserStatsFile {
+            version: "1.0".to_string(),
+            guilds: self.guilds.clone(),
+        };
+        let content = serde_json::to_string(&file)?;
+
+        // 先寫入暫存檔再改名，避免寫到一半時損壞原檔案
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(tmp_path, path)
+    }
+}
+
+/// 顯示猜歌遊戲排行榜
+#[poise::command(slash_command, guild_only)]
+pub async fn leaderboard(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let lang = i18n::language(ctx).await;
+
+    let lines: Vec<String> = {
+        let scores = ctx.data().scores.lock().await;
+        scores
+            .leaderboard(guild_id, 10)
+            .iter()
+            .enumerate()
+            .map(|(i, (user_id, stats))| {
+                tr!(
+                    lang,
+                    "leaderboard-entry",
+                    rank = format!("{:>2}", i + 1),
+                    user = format!("<@{}>", user_id),
+                    points = stats.total_points,
+                    accuracy = format!("{:.0}", stats.accuracy_rate * 100.0),
+                    streak = stats.best_streak
+                )
+            })
+            .collect()
+    };
+
+    if lines.is_empty() {
+        ctx.say(tr!(lang, "leaderboard-empty")).await?;
+        return Ok(());
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title(tr!(lang, "leaderboard-title"))
+                    .description(lines.join("\n"))
+                    .colour(0x1DB954),
+            )
+            .allowed_mentions(serenity::all::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 顯示個人猜歌統計
+#[poise::command(slash_command, guild_only)]
+pub async fn stats(
+    ctx: PoiseContext<'_>,
+    #[description = "要查看的使用者（預設為自己）"] user: Option<User>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let user = user.as_ref().unwrap_or_else(|| ctx.author());
+    let lang = i18n::language(ctx).await;
+
+    let Some(stats) = ctx.data().scores.lock().await.user(guild_id, user.id).cloned() else {
+        ctx.say(tr!(lang, "stats-empty", user = user.name.as_str())).await?;
+        return Ok(());
+    };
+
+    let embed = CreateEmbed::new()
+        .title(tr!(lang, "stats-title", user = user.name.as_str()))
+        .field(tr!(lang, "stats-field-points"), stats.total_points.to_string(), true)
+        .field(tr!(lang, "stats-field-games"), stats.total_games.to_string(), true)
+        .field(tr!(lang, "stats-field-accuracy"), format!("{:.1}%", stats.accuracy_rate * 100.0), true)
+        .field(
+            tr!(lang, "stats-field-answers"),
+            format!("{} / {}", stats.correct_answers, stats.wrong_answers),
+            true,
+        )
+        .field(
+            tr!(lang, "stats-field-answer-time"),
+            tr!(
+                lang,
+                "stats-seconds",
+                seconds = format!("{:.1}", stats.average_answer_time_ms as f64 / 1000.0)
+            ),
+            true,
+        )
+        .field(
+            tr!(lang, "stats-field-streak"),
+            format!("{} / {}", stats.current_streak, stats.best_streak),
+            true,
+        )
+        .colour(0x1DB954);
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// 匯出本伺服器的猜歌分數（管理員）
+#[poise::command(slash_command, guild_only, custom_data = Capability::QuizAdmin)]
+pub async fn stats_export(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let lang = i18n::language(ctx).await;
+
+    let content = ctx.data().scores.lock().await.export(guild_id)?;
+    ctx.send(
+        poise::CreateReply::default()
+            .content(tr!(lang, "stats-export-done"))
+            .attachment(serenity::all::CreateAttachment::bytes(content, "user_stats.json"))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 重設本伺服器或單一使用者的猜歌分數（管理員）
+#[poise::command(slash_command, guild_only, custom_data = Capability::QuizAdmin)]
+pub async fn stats_reset(
+    ctx: PoiseContext<'_>,
+    #[description = "只重設此使用者（預設為整個伺服器）"] user: Option<User>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    ctx.data()
+        .scores
+        .lock()
+        .await
+        .reset(guild_id, user.as_ref().map(|u| u.id))?;
+
+    let lang = i18n::language(ctx).await;
+    let message = match user {
+        Some(user) => tr!(lang, "stats-reset-user", user = user.name),
+        None => tr!(lang, "stats-reset-guild"),
+    };
+    ctx.send(poise::CreateReply::default().content(message).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit_ms() -> u64 {
+        ANSWER_TIME.as_millis() as u64
+    }
+
+    #[test]
+    fn instant_answers_get_the_full_speed_bonus() {
+        assert_eq!(points_for(0, 1), BASE_POINTS + MAX_SPEED_BONUS);
+    }
+
+    #[test]
+    fn speed_bonus_decreases_linearly_and_stops_at_the_limit() {
+        assert_eq!(points_for(limit_ms() / 2, 1), BASE_POINTS + MAX_SPEED_BONUS / 2);
+        assert_eq!(points_for(limit_ms(), 1), BASE_POINTS);
+        // 超過作答時間（例如延遲）不會變成負分
+        assert_eq!(points_for(limit_ms() * 3, 1), BASE_POINTS);
+    }
+
+    #[test]
+    fn streak_bonus_starts_at_the_second_answer_and_is_capped() {
+        let slow = limit_ms();
+        assert_eq!(points_for(slow, 0), BASE_POINTS);
+        assert_eq!(points_for(slow, 1), BASE_POINTS);
+        assert_eq!(points_for(slow, 2), BASE_POINTS + STREAK_BONUS);
+        assert_eq!(points_for(slow, 11), BASE_POINTS + MAX_STREAK_BONUS);
+        assert_eq!(points_for(slow, 50), BASE_POINTS + MAX_STREAK_BONUS);
+        assert_eq!(points_for(slow, u32::MAX), BASE_POINTS + MAX_STREAK_BONUS);
+    }
+}
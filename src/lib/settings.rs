@@ -0,0 +1,458 @@
+///lib/settings.rs
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use poise::ChoiceParameter;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serenity::all::{CreateEmbed, GuildId, RoleId};
+
+use crate::lib::announce::AnnounceSettings;
+use crate::lib::i18n::{self, tr};
+use crate::lib::permissions::Capability;
+use crate::lib::storage::backup;
+use crate::{Data, Error, PoiseContext, say_ephemeral};
+
+const SETTINGS_FILE: &str = "guild_settings.json";
+
+// 設定檔目前的格式版本，修改格式時加一並在 MIGRATIONS 加上對應的升級函式
+const SCHEMA_VERSION: u64 = 1;
+// 第 i 個函式把第 i + 1 版升級到第 i + 2 版，第 1 版是最早的格式
+const MIGRATIONS: [fn(&mut Value); (SCHEMA_VERSION - 1) as usize] = [];
+
+// Discord 語音允許的位元率範圍（kbps）
+const MIN_BITRATE_KBPS: u32 = 8;
+const MAX_BITRATE_KBPS: u32 = 510;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default, poise::ChoiceParameter)]
+pub enum Language {
+    #[default]
+    #[name = "繁體中文"]
+    #[serde(rename = "zh-TW")]
+    ZhTw,
+    #[name = "English"]
+    #[serde(rename = "en")]
+    En,
+}
+
+/// 單一伺服器的設定，未設定的項目使用 `Config` 的全域值
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct GuildSettings {
+    #[serde(default)]
+    pub announce: Option<AnnounceSettings>,
+    #[serde(default)]
+    pub permissions: HashMap<Capability, HashSet<RoleId>>,
+    // 未設定時由 songbird 自動決定
+    #[serde(default)]
+    pub bitrate_kbps: Option<u32>,
+    #[serde(default)]
+    pub idle_timeout_minutes: Option<u64>,
+    #[serde(default)]
+    pub idle_warning_seconds: Option<u64>,
+    #[serde(default)]
+    pub language: Option<Language>,
+}
+
+impl GuildSettings {
+    /// 設定給這個能力的身分組，空的代表沒有限制
+    pub fn roles(&self, capability: Capability) -> HashSet<RoleId> {
+        self.permissions.get(&capability).cloned().unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SettingsFile {
+    version: u64,
+    guilds: HashMap<GuildId, GuildSettings>,
+}
+
+/// 各伺服器的設定，保存在 `cache_dir` 下的 `guild_settings.json`
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    // 儲存時寫入的格式版本
+    version: u64,
+    guilds: HashMap<GuildId, GuildSettings>,
+}
+
+impl SettingsStore {
+    /// 從 `cache_dir` 載入設定並升級到目前的格式，沒有快取目錄時只保存在記憶體中
+    pub fn load(cache_dir: Option<&str>) -> SettingsStore {
+        let path = cache_dir.map(|dir| Path::new(dir).join(SETTINGS_FILE));
+        SettingsStore::load_with(path, &MIGRATIONS)
+    }
+
+    // 目前的版本是 `migrations.len() + 1`，測試時可以換成其他升級函式
+    fn load_with(path: Option<PathBuf>, migrations: &[fn(&mut Value)]) -> SettingsStore {
+        let schema_version = migrations.len() as u64 + 1;
+
+        let mut value = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => match serde_json::from_str::<Value>(&content) {
+                Ok(value) => value,
+                Err(e) => {
+                    println!("[設定] ⚠️ 無法解析 {}: {:?}", SETTINGS_FILE, e);
+                    backup(path.as_deref(), "corrupt");
+                    json!({ "version": schema_version, "guilds": {} })
+                }
+            },
+            _ => json!({ "version": schema_version, "guilds": {} }),
+        };
+
+        let version = value.get("version").and_then(Value::as_u64).unwrap_or(1).max(1);
+        if version > schema_version {
+            println!(
+                "[設定] ⚠️ {} 的版本 ({}) 比目前支援的 ({}) 新，未知的欄位會被忽略",
+                SETTINGS_FILE, version, schema_version
+            );
+            // 下次儲存時會以目前的版本覆寫，先保留新版本的檔案
+            backup(path.as_deref(), &format!("v{}", version));
+        }
+
+        let migrated = version < schema_version;
+        if migrated {
+            // 升級前先備份，升級出問題時還能找回原本的設定
+            backup(path.as_deref(), &format!("v{}", version));
+        }
+        for from in version..schema_version {
+            migrations[(from - 1) as usize](&mut value);
+            value["version"] = json!(from + 1);
+            println!("[設定] ⬆️ 設定檔已從第 {} 版升級到第 {} 版", from, from + 1);
+        }
+
+        let guilds = match serde_json::from_value::<SettingsFile>(value) {
+            Ok(file) => file.guilds,
+            Err(e) => {
+                println!("[設定] ⚠️ 無法讀取伺服器設定: {:?}", e);
+                backup(path.as_deref(), "corrupt");
+                HashMap::new()
+            }
+        };
+
+        let store = SettingsStore {
+            path,
+            version: schema_version,
+            guilds,
+        };
+        if migrated {
+            if let Err(e) = store.save() {
+                println!("[設定] ⚠️ 無法儲存升級後的設定: {:?}", e);
+            }
+        }
+        store
+    }
+
+    pub fn get(&self, guild_id: GuildId) -> GuildSettings {
+        self.guilds.get(&guild_id).cloned().unwrap_or_default()
+    }
+
+    /// 修改伺服器的設定並立即寫入檔案
+    pub fn update<T>(&mut self, guild_id: GuildId, f: impl FnOnce(&mut GuildSettings) -> T) -> std::io::Result<T> {
+        let result = f(self.guilds.entry(guild_id).or_default());
+        self.save()?;
+        Ok(result)
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        let file = SettingsFile {
+            version: self.version,
+            guilds: self.guilds.clone(),
+        };
+        let content = serde_json::to_string_pretty(&file)?;
+
+        // 先寫入暫存檔再改名，避免寫到一半時損壞原檔案
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(tmp_path, path)
+    }
+}
+
+// `unit` 是訊息目錄中帶有 `$value` 參數的單位訊息
+fn or_default(lang: Language, value: Option<u64>, default: u64, unit: &str) -> String {
+    match value {
+        Some(value) => tr!(lang, unit, value = value),
+        None => tr!(lang, "settings-default", value = tr!(lang, unit, value = default)),
+    }
+}
+
+fn settings_embed(lang: Language, data: &Data, settings: &GuildSettings) -> CreateEmbed {
+    let announce = match settings.announce {
+        Some(announce) if announce.enabled => format!("<#{}>", announce.channel_id),
+        _ => tr!(lang, "settings-off"),
+    };
+    let bitrate = match settings.bitrate_kbps {
+        Some(kbps) => tr!(lang, "settings-unit-kbps", value = kbps),
+        None => tr!(lang, "settings-default", value = tr!(lang, "settings-auto")),
+    };
+    let language = match settings.language {
+        Some(language) => language.name().to_string(),
+        None => tr!(lang, "settings-default", value = data.config.language.name()),
+    };
+    let roles = settings.permissions.values().map(HashSet::len).sum::<usize>();
+
+    CreateEmbed::new()
+        .title(tr!(lang, "settings-title"))
+        .field(tr!(lang, "settings-field-announce"), announce, true)
+        .field(tr!(lang, "settings-field-bitrate"), bitrate, true)
+        .field(tr!(lang, "settings-field-language"), language, true)
+        .field(
+            tr!(lang, "settings-field-idle-timeout"),
+            or_default(
+                lang,
+                settings.idle_timeout_minutes,
+                data.config.idle_timeout_minutes,
+                "settings-unit-minutes",
+            ),
+            true,
+        )
+        .field(
+            tr!(lang, "settings-field-idle-warning"),
+            or_default(
+                lang,
+                settings.idle_warning_seconds,
+                data.config.idle_warning_seconds,
+                "settings-unit-seconds",
+            ),
+            true,
+        )
+        .field(tr!(lang, "settings-field-permissions"), tr!(lang, "settings-roles", count = roles), true)
+        .colour(0x1DB954)
+}
+
+/// 伺服器設定
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "settings_view",
+        "settings_bitrate",
+        "settings_idle_timeout",
+        "settings_idle_warning",
+        "settings_language"
+    )
+)]
+pub async fn settings(_ctx: PoiseContext<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 顯示這個伺服器的設定
+#[poise::command(slash_command, guild_only, rename = "view")]
+pub async fn settings_view(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let lang = i18n::language(ctx).await;
+
+    let settings = ctx.data().settings.lock().await.get(guild_id);
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(settings_embed(lang, ctx.data(), &settings))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 設定語音位元率，不填則交給 Discord 自動決定
+#[poise::command(slash_command, guild_only, rename = "bitrate", custom_data = Capability::Admin)]
+pub async fn settings_bitrate(
+    ctx: PoiseContext<'_>,
+    #[description = "位元率（kbps）"]
+    #[min = 8]
+    #[max = 510]
+    kbps: Option<u32>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let kbps = kbps.map(|kbps| kbps.clamp(MIN_BITRATE_KBPS, MAX_BITRATE_KBPS));
+
+    ctx.data()
+        .settings
+        .lock()
+        .await
+        .update(guild_id, |settings| settings.bitrate_kbps = kbps)?;
+
+    // 已經在語音頻道中時立即套用
+    if let Some(call) = songbird::get(ctx.serenity_context()).await.and_then(|m| m.get(guild_id)) {
+        call.lock().await.set_bitrate(crate::lib::voice::bitrate(kbps));
+    }
+
+    let lang = i18n::language(ctx).await;
+    let message = match kbps {
+        Some(kbps) => tr!(lang, "settings-bitrate-set", kbps = kbps),
+        None => tr!(lang, "settings-bitrate-auto"),
+    };
+    say_ephemeral(ctx, message).await
+}
+
+/// 設定暫停或頻道中沒有人多久後離開語音頻道，不填則使用預設值
+#[poise::command(slash_command, guild_only, rename = "idle_timeout", custom_data = Capability::Admin)]
+pub async fn settings_idle_timeout(
+    ctx: PoiseContext<'_>,
+    #[description = "分鐘（0 表示不會自動離開）"] minutes: Option<u64>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    ctx.data()
+        .settings
+        .lock()
+        .await
+        .update(guild_id, |settings| settings.idle_timeout_minutes = minutes)?;
+
+    let lang = i18n::language(ctx).await;
+    let message = match minutes {
+        Some(0) => tr!(lang, "settings-idle-timeout-off"),
+        Some(minutes) => tr!(lang, "settings-idle-timeout-set", minutes = minutes),
+        None => tr!(
+            lang,
+            "settings-reset",
+            value = tr!(lang, "settings-unit-minutes", value = ctx.data().config.idle_timeout_minutes)
+        ),
+    };
+    say_ephemeral(ctx, message).await
+}
+
+/// 設定離開語音頻道前多久發出警告，不填則使用預設值
+#[poise::command(slash_command, guild_only, rename = "idle_warning", custom_data = Capability::Admin)]
+pub async fn settings_idle_warning(
+    ctx: PoiseContext<'_>,
+    #[description = "秒"] seconds: Option<u64>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    ctx.data()
+        .settings
+        .lock()
+        .await
+        .update(guild_id, |settings| settings.idle_warning_seconds = seconds)?;
+
+    let lang = i18n::language(ctx).await;
+    let message = match seconds {
+        Some(seconds) => tr!(lang, "settings-idle-warning-set", seconds = seconds),
+        None => tr!(
+            lang,
+            "settings-reset",
+            value = tr!(lang, "settings-unit-seconds", value = ctx.data().config.idle_warning_seconds)
+        ),
+    };
+    say_ephemeral(ctx, message).await
+}
+
+/// 設定機器人在這個伺服器使用的語言
+#[poise::command(slash_command, guild_only, rename = "language", custom_data = Capability::Admin)]
+pub async fn settings_language(
+    ctx: PoiseContext<'_>,
+    #[description = "語言"] language: Language,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    ctx.data()
+        .settings
+        .lock()
+        .await
+        .update(guild_id, |settings| settings.language = Some(language))?;
+
+    // 用新設定的語言回覆
+    say_ephemeral(ctx, tr!(language, "settings-language-set", language = language.name())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("settings-test-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // 測試用的第 1 版到第 2 版升級：`bitrate` 改名為 `bitrate_kbps`
+    fn rename_bitrate(value: &mut Value) {
+        let Some(guilds) = value["guilds"].as_object_mut() else {
+            return;
+        };
+        for guild in guilds.values_mut() {
+            if let Some(bitrate) = guild.as_object_mut().and_then(|guild| guild.remove("bitrate")) {
+                guild["bitrate_kbps"] = bitrate;
+            }
+        }
+    }
+
+    #[test]
+    fn older_file_is_backed_up_and_migrated() {
+        let dir = temp_dir("older");
+        let path = dir.join(SETTINGS_FILE);
+        let original = r#"{"version":1,"guilds":{"123":{"bitrate":96,"language":"en"}}}"#;
+        std::fs::write(&path, original).unwrap();
+
+        let store = SettingsStore::load_with(Some(path.clone()), &[rename_bitrate]);
+        let backup = std::fs::read_to_string(dir.join("guild_settings.json.v1.bak"));
+        let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let settings = store.get(GuildId::new(123));
+        assert_eq!(settings.bitrate_kbps, Some(96));
+        assert_eq!(settings.language, Some(Language::En));
+        assert_eq!(backup.unwrap(), original);
+        assert_eq!(saved["version"], 2);
+        assert_eq!(saved["guilds"]["123"]["bitrate_kbps"], 96);
+    }
+
+    #[test]
+    fn corrupt_file_is_backed_up_and_replaced_with_defaults() {
+        let dir = temp_dir("corrupt");
+        std::fs::write(dir.join(SETTINGS_FILE), "{ not json").unwrap();
+
+        let store = SettingsStore::load(dir.to_str());
+        let backup = std::fs::read_to_string(dir.join("guild_settings.json.corrupt.bak"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(store.guilds.is_empty());
+        assert_eq!(backup.unwrap(), "{ not json");
+    }
+
+    #[test]
+    fn newer_file_is_backed_up_and_read_without_unknown_fields() {
+        let dir = temp_dir("newer");
+        let path = dir.join(SETTINGS_FILE);
+        let original = r#"{"version":99,"guilds":{"123":{"bitrate_kbps":64,"from_the_future":true}}}"#;
+        std::fs::write(&path, original).unwrap();
+
+        let store = SettingsStore::load(dir.to_str());
+        let backup = std::fs::read_to_string(dir.join("guild_settings.json.v99.bak"));
+        let current = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(store.get(GuildId::new(123)).bitrate_kbps, Some(64));
+        assert_eq!(backup.unwrap(), original);
+        // 只有讀取，不會在載入時覆寫新版本的檔案
+        assert_eq!(current, original);
+    }
+
+    #[test]
+    fn current_file_is_loaded_without_a_backup() {
+        let dir = temp_dir("current");
+        std::fs::write(dir.join(SETTINGS_FILE), r#"{"version":1,"guilds":{"123":{"idle_timeout_minutes":5}}}"#)
+            .unwrap();
+
+        let store = SettingsStore::load(dir.to_str());
+        let backups = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path().extension().is_some_and(|ext| ext == "bak"))
+            .count();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(store.get(GuildId::new(123)).idle_timeout_minutes, Some(5));
+        assert_eq!(backups, 0);
+    }
+}
@@ -0,0 +1,80 @@
+///lib/shutdown.rs
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::all::{Context, GuildId, OnlineStatus, ShardManager};
+
+use crate::lib::voice;
+use crate::Data;
+
+// 收到停止訊號後清理的時間上限，超過時直接關閉連線
+// 兩者加起來要小於 `docker stop` 預設的 10 秒，否則會被 SIGKILL 強制結束
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+// 關閉 shard 的時間上限，超過時直接結束程式
+const SHARD_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 等待 SIGINT（Ctrl+C）或 SIGTERM（`docker stop`）
+async fn wait_for_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = sigterm.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                println!("[關閉] ⚠️ 無法監聽 SIGTERM: {:?}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
+
+/// 在背景等待停止訊號，收到後關閉 Spotify Connect、離開所有語音頻道並關閉 Discord 連線
+pub fn spawn_signal_handler(ctx: Context, data: Data, shard_manager: Arc<ShardManager>) {
+    tokio::spawn(async move {
+        let signal = wait_for_signal().await;
+        println!("[關閉] 收到 {}，準備關閉...", signal);
+
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, cleanup(&ctx, &data)).await.is_err() {
+            println!("[關閉] ⚠️ 清理超過 {} 秒，直接關閉連線", SHUTDOWN_TIMEOUT.as_secs());
+        }
+
+        // 關閉所有 shard 後 client.start() 會返回，main 隨之結束
+        if tokio::time::timeout(SHARD_SHUTDOWN_TIMEOUT, shard_manager.shutdown_all())
+            .await
+            .is_err()
+        {
+            println!("[關閉] ⚠️ 無法正常關閉 Discord 連線，直接結束");
+            std::process::exit(0);
+        }
+        println!("[關閉] ✓ 已關閉");
+    });
+}
+
+async fn cleanup(ctx: &Context, data: &Data) {
+    // 正在播放的曲目不會再收到結束事件，先寫入播放紀錄
+    data.history.lock().await.finish_current();
+
+    // 再關閉 Spirc，讓 Spotify App 立即移除這個裝置
+    data.connect.disable().await;
+
+    if let Some(manager) = songbird::get(ctx).await {
+        let guilds: Vec<GuildId> = manager.iter().map(|(guild_id, _)| GuildId::new(guild_id.0.get())).collect();
+        for guild_id in guilds {
+            println!("[關閉] 離開語音頻道 (Guild: {:?})", guild_id);
+            voice::leave(ctx, data, guild_id).await;
+        }
+    }
+
+    ctx.set_presence(None, OnlineStatus::Online);
+    println!("[關閉] ✓ 已儲存播放紀錄，Spotify Connect 已停用，已離開所有語音頻道");
+}
@@ -0,0 +1,152 @@
+///lib/stage.rs
+use std::collections::HashMap;
+
+use serenity::all::{
+    ChannelId, ChannelType, Context, CreateStageInstance, EditStageInstance, EditVoiceState, GuildChannel,
+    GuildId, StageInstance,
+};
+
+use crate::Data;
+
+// Discord 舞台主題的長度上限
+const MAX_TOPIC_LEN: usize = 120;
+
+struct StageSession {
+    channel_id: ChannelId,
+    // 舞台是由機器人開啟的，離開時要負責關閉
+    created_instance: bool,
+}
+
+/// 機器人目前所在的舞台頻道
+#[derive(Default)]
+pub struct StageState {
+    sessions: HashMap<GuildId, StageSession>,
+}
+
+impl StageState {
+    pub fn new() -> StageState {
+        StageState::default()
+    }
+}
+
+fn stage_channel(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> Option<GuildChannel> {
+    let guild = ctx.cache.guild(guild_id)?;
+    guild
+        .channels
+        .get(&channel_id)
+        .filter(|channel| channel.kind == ChannelType::Stage)
+        .cloned()
+}
+
+fn truncate_topic(topic: &str) -> String {
+    if topic.chars().count() <= MAX_TOPIC_LEN {
+        return topic.to_string();
+    }
+    let mut truncated: String = topic.chars().take(MAX_TOPIC_LEN - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// 加入頻道後呼叫：如果是舞台頻道就成為發言者，失敗時改為舉手請求發言
+pub async fn on_joined(ctx: &Context, data: &Data, guild_id: GuildId, channel_id: ChannelId) {
+    let Some(channel) = stage_channel(ctx, guild_id, channel_id) else {
+        data.stage.lock().await.sessions.remove(&guild_id);
+        return;
+    };
+
+    println!("[舞台] 🎙️ 加入舞台頻道 {}，嘗試成為發言者...", channel.name);
+    data.stage.lock().await.sessions.insert(
+        guild_id,
+        StageSession {
+            channel_id,
+            created_instance: false,
+        },
+    );
+
+    // 有「禁言成員」權限時可以直接上台
+    match channel.edit_own_voice_state(ctx, EditVoiceState::new().suppress(false)).await {
+        Ok(()) => println!("[舞台] ✓ 已成為發言者"),
+        Err(e) => {
+            println!("[舞台] ⚠️ 無法直接上台（{:?}），改為請求發言", e);
+            match channel.edit_own_voice_state(ctx, EditVoiceState::new().request_to_speak(true)).await {
+                Ok(()) => println!("[舞台] ✋ 已請求發言，等待主持人邀請"),
+                Err(e) => println!("[舞台] ✗ 請求發言失敗: {:?}", e),
+            }
+        }
+    }
+}
+
+/// 依照目前播放的曲目更新舞台主題，舞台還沒開始時會自動開啟
+pub async fn update_topic(ctx: &Context, data: &Data, topic: &str) {
+    let topic = truncate_topic(topic);
+    let sessions: Vec<(GuildId, ChannelId)> = data
+        .stage
+        .lock()
+        .await
+        .sessions
+        .iter()
+        .map(|(guild_id, session)| (*guild_id, session.channel_id))
+        .collect();
+
+    for (guild_id, channel_id) in sessions {
+        let result = match channel_id.get_stage_instance(&ctx.http).await {
+            Ok(_) => channel_id
+                .edit_stage_instance(ctx, EditStageInstance::new().topic(topic.clone()))
+                .await
+                .map(|_| false),
+            Err(_) => channel_id
+                .create_stage_instance(ctx, CreateStageInstance::new(topic.clone()))
+                .await
+                .map(|_| true),
+        };
+
+        match result {
+            Ok(created) => {
+                if created {
+                    println!("[舞台] ✓ 已開啟舞台: {}", topic);
+                    if let Some(session) = data.stage.lock().await.sessions.get_mut(&guild_id) {
+                        session.created_instance = true;
+                    }
+                }
+            }
+            Err(e) => println!("[舞台] ⚠️ 無法更新舞台主題: {:?}", e),
+        }
+    }
+}
+
+/// 離開頻道前呼叫：關閉由機器人開啟的舞台
+pub async fn cleanup(ctx: &Context, data: &Data, guild_id: GuildId) {
+    let Some(session) = data.stage.lock().await.sessions.remove(&guild_id) else {
+        return;
+    };
+
+    if session.created_instance {
+        match session.channel_id.delete_stage_instance(&ctx.http).await {
+            Ok(()) => println!("[舞台] ✓ 已關閉舞台"),
+            Err(e) => println!("[舞台] ⚠️ 無法關閉舞台: {:?}", e),
+        }
+    }
+}
+
+/// 舞台被主持人結束時離開頻道
+pub async fn on_stage_ended(ctx: &Context, data: &Data, stage_instance: &StageInstance) {
+    let guild_id = stage_instance.guild_id;
+    let is_ours = data
+        .stage
+        .lock()
+        .await
+        .sessions
+        .get(&guild_id)
+        .is_some_and(|session| session.channel_id == stage_instance.channel_id);
+    if !is_ours {
+        return;
+    }
+
+    println!("[舞台] 🎬 舞台已結束，離開語音頻道");
+    data.stage.lock().await.sessions.remove(&guild_id);
+
+    let _ = data.connect.pause().await;
+    if let Some(manager) = songbird::get(ctx).await {
+        let _ = manager.remove(guild_id).await;
+    }
+}
@@ -0,0 +1,16 @@
+///lib/storage.rs
+use std::path::Path;
+
+/// 把 `cache_dir` 下的 JSON 檔複製一份，例如 `history.json.corrupt.bak`
+///
+/// 檔案無法解析時先備份再以空白資料繼續，下次儲存時才不會把原本的資料蓋掉
+pub fn backup(path: Option<&Path>, suffix: &str) {
+    let Some(path) = path.filter(|path| path.exists()) else {
+        return;
+    };
+    let backup_path = path.with_extension(format!("json.{}.bak", suffix));
+    match std::fs::copy(path, &backup_path) {
+        Ok(_) => println!("[備份] 💾 已備份 {} 到 {}", path.display(), backup_path.display()),
+        Err(e) => println!("[備份] ⚠️ 無法備份 {}: {:?}", path.display(), e),
+    }
+}
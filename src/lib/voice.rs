@@ -0,0 +1,316 @@
+///lib/voice.rs
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use serenity::all::{ChannelId, Context, GuildId, UserId};
+use serenity::async_trait;
+use songbird::events::context_data::DisconnectReason;
+use songbird::events::{CoreEvent, Event, EventContext, EventHandler as VoiceEventHandler, TrackEvent};
+use songbird::input::{Input, RawAdapter};
+use songbird::Call;
+use tokio::sync::Mutex;
+
+use crate::lib::connect::PlaybackError;
+use crate::lib::i18n::tr;
+use crate::lib::stage;
+use crate::Data;
+
+// 語音連線中斷後重新加入的次數與退避時間
+const MAX_REJOIN_ATTEMPTS: u32 = 5;
+const MAX_REJOIN_BACKOFF: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    // 正在重新加入的伺服器，避免同時有多個重試流程
+    static ref REJOINING: std::sync::Mutex<HashSet<GuildId>> = std::sync::Mutex::new(HashSet::new());
+}
+
+/// 使用者目前所在的語音頻道
+pub fn user_voice_channel(ctx: &Context, user_id: UserId) -> Option<(GuildId, ChannelId)> {
+    ctx.cache.guilds().iter().find_map(|gid| {
+        ctx.cache.guild(gid).and_then(|guild| {
+            guild
+                .voice_states
+                .get(&user_id)
+                .and_then(|state| state.channel_id.map(|ch| (gid.to_owned(), ch)))
+        })
+    })
+}
+
+/// 印出訊息，並在有設定 `log_channel_id` 時回報到記錄頻道（使用 `Config` 的預設語言）
+pub async fn report(ctx: &Context, data: &Data, message: String) {
+    println!("{}", message);
+    if let Some(channel_id) = data.config.log_channel_id {
+        if let Err(e) = ChannelId::new(channel_id).say(&ctx.http, &message).await {
+            println!("⚠️ 無法傳送到記錄頻道: {:?}", e);
+        }
+    }
+}
+
+/// 離開語音頻道，離開前先關閉機器人開啟的舞台
+pub async fn leave(ctx: &Context, data: &Data, guild_id: GuildId) {
+    stage::cleanup(ctx, data, guild_id).await;
+    if let Some(manager) = songbird::get(ctx).await {
+        let _ = manager.remove(guild_id).await;
+    }
+}
+
+/// 伺服器設定的位元率，未設定時交給 songbird 自動決定
+pub fn bitrate(kbps: Option<u32>) -> songbird::driver::Bitrate {
+    match kbps {
+        Some(kbps) => songbird::driver::Bitrate::BitsPerSecond(kbps as i32 * 1000),
+        None => songbird::driver::Bitrate::Auto,
+    }
+}
+
+/// 重新把 Spotify 的音訊接到 songbird，舊的輸入會先停止
+pub async fn attach_sink(ctx: &Context, data: &Data, guild_id: GuildId, call: &Arc<Mutex<Call>>) {
+    let kbps = data.settings.lock().await.get(guild_id).bitrate_kbps;
+    let mut handler = call.lock().await;
+    handler.stop();
+
+    println!("🎵 準備音訊源...");
+    let source: Input = RawAdapter::new(data.player.lock().await.emitted_sink.clone(), 48000, 2).into();
+
+    handler.set_bitrate(bitrate(kbps));
+    let track_handle = handler.play_input(source);
+    println!("✓ 開始播放音訊，軌道 UUID: {:?}", track_handle.uuid());
+
+    // 輸入發生錯誤時重新接上（stop() 觸發的 End 事件不處理，避免無限迴圈）
+    let events = VoiceEvents {
+        ctx: ctx.clone(),
+        data: data.clone(),
+        guild_id,
+    };
+    if let Err(e) = track_handle.add_event(Event::Track(TrackEvent::Error), events) {
+        println!("⚠️ 無法註冊軌道事件: {:?}", e);
+    }
+}
+
+/// 註冊語音連線中斷與重新連線的事件，每次加入頻道後呼叫
+pub async fn register_driver_events(ctx: &Context, data: &Data, guild_id: GuildId, call: &Arc<Mutex<Call>>) {
+    let mut handler = call.lock().await;
+
+    // 同一個 Call 重複加入頻道時先清掉舊的事件
+    handler.remove_all_global_events();
+    for event in [CoreEvent::DriverDisconnect, CoreEvent::DriverReconnect] {
+        handler.add_global_event(
+            Event::Core(event),
+            VoiceEvents {
+                ctx: ctx.clone(),
+                data: data.clone(),
+                guild_id,
+            },
+        );
+    }
+}
+
+struct VoiceEvents {
+    ctx: Context,
+    data: Data,
+    guild_id: GuildId,
+}
+
+#[async_trait]
+impl VoiceEventHandler for VoiceEvents {
+    async fn act(&self, event: &EventContext<'_>) -> Option<Event> {
+        match event {
+            EventContext::DriverDisconnect(disconnect) => {
+                // reason 為 None 或 Requested 代表是我們自己離開或切換頻道
+                let reason = disconnect.reason?;
+                if reason == DisconnectReason::Requested {
+                    return None;
+                }
+                let channel_id = ChannelId::new(disconnect.channel_id?.0.get());
+
+                let lang = self.data.config.language;
+                report(
+                    &self.ctx,
+                    &self.data,
+                    tr!(
+                        lang,
+                        "voice-disconnected",
+                        reason = format!("{:?} / {:?}", disconnect.kind, reason),
+                        channel = format!("<#{}>", channel_id)
+                    ),
+                )
+                .await;
+
+                let ctx = self.ctx.clone();
+                let data = self.data.clone();
+                let guild_id = self.guild_id;
+                tokio::spawn(async move {
+                    rejoin(&ctx, &data, guild_id, channel_id).await;
+                });
+            }
+            EventContext::DriverReconnect(_) => {
+                report(&self.ctx, &self.data, tr!(self.data.config.language, "voice-reconnected")).await;
+            }
+            EventContext::Track(_) => {
+                println!("⚠️ 音訊輸入發生錯誤，重新接上 Spotify 音訊...");
+                if let Some(call) = songbird::get(&self.ctx).await.and_then(|m| m.get(self.guild_id)) {
+                    attach_sink(&self.ctx, &self.data, self.guild_id, &call).await;
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+// 以指數退避重新加入最後的頻道，成功後重新接上 EmittedSink
+async fn rejoin(ctx: &Context, data: &Data, guild_id: GuildId, channel_id: ChannelId) {
+    if !REJOINING.lock().unwrap().insert(guild_id) {
+        return;
+    }
+
+    let mut recovered = false;
+    for attempt in 1..=MAX_REJOIN_ATTEMPTS {
+        let backoff = Duration::from_secs(1 << attempt).min(MAX_REJOIN_BACKOFF);
+        tokio::time::sleep(backoff).await;
+
+        let Some(manager) = songbird::get(ctx).await else {
+            break;
+        };
+        // 在等待期間已經被移除（例如閒置離開），就不再重試
+        if manager.get(guild_id).is_none() {
+            println!("ℹ️ 語音連線已被移除，停止重新加入");
+            recovered = true;
+            break;
+        }
+
+        match manager.join(guild_id, channel_id).await {
+            Ok(call) => {
+                register_driver_events(ctx, data, guild_id, &call).await;
+                stage::on_joined(ctx, data, guild_id, channel_id).await;
+                attach_sink(ctx, data, guild_id, &call).await;
+                let message = tr!(
+                    data.config.language,
+                    "voice-rejoined",
+                    channel = format!("<#{}>", channel_id),
+                    attempt = attempt
+                );
+                report(ctx, data, message).await;
+                recovered = true;
+                break;
+            }
+            Err(e) => {
+                println!("✗ 重新加入失敗（{}/{}）: {:?}", attempt, MAX_REJOIN_ATTEMPTS, e);
+            }
+        }
+    }
+
+    if !recovered {
+        report(
+            ctx,
+            data,
+            tr!(
+                data.config.language,
+                "voice-rejoin-failed",
+                channel = format!("<#{}>", channel_id),
+                attempts = MAX_REJOIN_ATTEMPTS
+            ),
+        )
+        .await;
+        leave(ctx, data, guild_id).await;
+    }
+
+    REJOINING.lock().unwrap().remove(&guild_id);
+}
+
+/// 確保機器人在指定頻道並播放音訊，失敗時回傳 `false`
+pub async fn join_and_play(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> bool {
+    let Some(manager) = songbird::get(ctx).await else {
+        return false;
+    };
+
+    // 檢查是否需要加入或切換頻道
+    let call = if let Some(call) = manager.get(guild_id) {
+        let current_channel = call.lock().await.current_channel();
+
+        let songbird_channel_id: songbird::id::ChannelId = channel_id.into();
+        if current_channel != Some(songbird_channel_id) {
+            println!("🔄 切換到使用者所在頻道...");
+            leave(ctx, data, guild_id).await;
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+            match manager.join(guild_id, channel_id).await {
+                Ok(call) => {
+                    println!("✓ 成功切換頻道");
+                    register_driver_events(ctx, data, guild_id, &call).await;
+                    stage::on_joined(ctx, data, guild_id, channel_id).await;
+                    call
+                }
+                Err(e) => {
+                    println!("✗ 切換頻道失敗: {:?}", e);
+                    return false;
+                }
+            }
+        } else {
+            call
+        }
+    } else {
+        // 還沒加入，現在加入
+        println!("🎤 加入語音頻道...");
+        match manager.join(guild_id, channel_id).await {
+            Ok(call) => {
+                println!("✓ 成功加入語音頻道");
+                register_driver_events(ctx, data, guild_id, &call).await;
+                stage::on_joined(ctx, data, guild_id, channel_id).await;
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                call
+            }
+            Err(e) => {
+                println!("✗ 加入語音頻道失敗: {:?}", e);
+                return false;
+            }
+        }
+    };
+
+    attach_sink(ctx, data, guild_id, &call).await;
+    true
+}
+
+/// 使用者切換頻道（包含跨伺服器）時跟著移動，並從原本的位置繼續播放
+pub async fn follow_user(ctx: &Context, data: &Data, guild_id: GuildId, channel_id: ChannelId) {
+    let Some(manager) = songbird::get(ctx).await else {
+        return;
+    };
+
+    let was_playing = data.idle.lock().await.is_playing();
+
+    // 移動期間先暫停，避免音訊送進已經沒有人的頻道
+    if was_playing {
+        if let Err(PlaybackError::Spirc(e)) = data.connect.pause().await {
+            println!("⚠️ 暫停失敗: {:?}", e);
+        }
+    }
+
+    // 跨伺服器移動時離開舊的伺服器
+    for other in ctx.cache.guilds() {
+        if other != guild_id && manager.get(other).is_some() {
+            println!("👋 離開舊伺服器的語音頻道 (Guild: {:?})", other);
+            leave(ctx, data, other).await;
+        }
+    }
+
+    // 丟掉移動前緩衝的音訊
+    data.player.lock().await.emitted_sink.reset();
+
+    if !join_and_play(ctx, data, guild_id, channel_id).await {
+        return;
+    }
+
+    if was_playing {
+        println!("▶️ 已移動到新頻道，從原本的位置繼續播放");
+        if let Err(PlaybackError::Spirc(e)) = data.connect.play().await {
+            println!("⚠️ 繼續播放失敗: {:?}", e);
+        }
+    }
+}
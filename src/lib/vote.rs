@@ -0,0 +1,242 @@
+///lib/vote.rs
+use std::collections::{HashMap, HashSet};
+
+use serenity::all::{ChannelId, GuildId, UserId};
+
+use crate::lib::connect::PlaybackError;
+use crate::lib::i18n::{self, tr};
+use crate::lib::idle::human_listeners;
+use crate::lib::permissions::Capability;
+use crate::lib::queue::connect_unavailable;
+use crate::lib::settings::Language;
+use crate::{Error, PoiseContext};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum VoteKind {
+    Skip,
+    Pause,
+}
+
+impl VoteKind {
+    // 訊息目錄中的動作名稱
+    fn label(&self) -> &'static str {
+        match self {
+            VoteKind::Skip => "vote-action-skip",
+            VoteKind::Pause => "vote-action-pause",
+        }
+    }
+
+    fn emoji(&self) -> &'static str {
+        match self {
+            VoteKind::Skip => "⏭️",
+            VoteKind::Pause => "⏸️",
+        }
+    }
+}
+
+/// 各伺服器目前這首歌的投票，換歌時清空
+#[derive(Default)]
+pub struct VoteState {
+    votes: HashMap<(GuildId, VoteKind), HashSet<UserId>>,
+}
+
+impl VoteState {
+    pub fn new() -> VoteState {
+        VoteState::default()
+    }
+
+    /// 換歌時呼叫
+    pub fn reset(&mut self) {
+        self.votes.clear();
+    }
+
+    // 已經投過票時回傳 false
+    fn cast(&mut self, guild_id: GuildId, kind: VoteKind, user_id: UserId) -> bool {
+        self.votes.entry((guild_id, kind)).or_default().insert(user_id)
+    }
+
+    // 只計算仍在頻道中的聽眾，中途離開的人不算
+    fn count(&self, guild_id: GuildId, kind: VoteKind, listeners: &[UserId]) -> usize {
+        self.votes
+            .get(&(guild_id, kind))
+            .map(|voters| listeners.iter().filter(|user| voters.contains(user)).count())
+            .unwrap_or(0)
+    }
+
+    fn clear(&mut self, guild_id: GuildId, kind: VoteKind) {
+        self.votes.remove(&(guild_id, kind));
+    }
+}
+
+/// 達到門檻需要的票數，至少一票
+fn required_votes(listeners: usize, threshold_percent: u64) -> usize {
+    let required = (listeners as u64 * threshold_percent).div_ceil(100);
+    required.max(1) as usize
+}
+
+// 機器人目前所在的語音頻道
+async fn bot_channel(ctx: &serenity::all::Context, guild_id: GuildId) -> Option<ChannelId> {
+    let call = songbird::get(ctx).await?.get(guild_id)?;
+    let channel = call.lock().await.current_channel()?;
+    Some(ChannelId::new(channel.0.get()))
+}
+
+async fn run_vote(ctx: PoiseContext<'_>, kind: VoteKind) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let data = ctx.data();
+    let lang = i18n::language(ctx).await;
+    let action = tr!(lang, kind.label());
+
+    let Some(channel_id) = bot_channel(ctx.serenity_context(), guild_id).await else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(tr!(lang, "vote-bot-not-in-voice"))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let listeners = human_listeners(ctx.serenity_context(), guild_id, channel_id);
+    let user_id = ctx.author().id;
+    // 設定的擁有者可以直接執行，不需要投票
+    let is_owner = user_id.get() == data.config.discord_user_id;
+    if !is_owner && !listeners.contains(&user_id) {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(tr!(lang, "vote-listeners-only", channel = format!("<#{}>", channel_id)))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let required = required_votes(listeners.len(), data.config.vote_threshold_percent);
+    let (newly_cast, count) = {
+        let mut votes = data.votes.lock().await;
+        let newly_cast = votes.cast(guild_id, kind, user_id);
+        (newly_cast, votes.count(guild_id, kind, &listeners))
+    };
+
+    if !is_owner && count < required {
+        let message = if newly_cast {
+            tr!(
+                lang,
+                "vote-cast",
+                user = ctx.author().name.as_str(),
+                action = action.as_str(),
+                count = count,
+                required = required
+            )
+        } else {
+            tr!(lang, "vote-already-cast", count = count, required = required)
+        };
+        ctx.say(message).await?;
+        return Ok(());
+    }
+
+    data.votes.lock().await.clear(guild_id, kind);
+    let result = match kind {
+        VoteKind::Skip => data.connect.next().await,
+        VoteKind::Pause => data.connect.pause().await,
+    };
+
+    match result {
+        Ok(()) => {
+            let message = if is_owner && count < required {
+                tr!(
+                    lang,
+                    "vote-owner-bypass",
+                    emoji = kind.emoji(),
+                    user = ctx.author().name.as_str(),
+                    action = action.as_str()
+                )
+            } else {
+                tr!(
+                    lang,
+                    "vote-passed",
+                    count = count,
+                    required = required,
+                    action = action.as_str()
+                )
+            };
+            println!("[投票] {}", message);
+            ctx.say(message).await?;
+        }
+        Err(PlaybackError::Unavailable) => {
+            ctx.say(tr!(lang, connect_unavailable(data).await)).await?;
+        }
+        Err(PlaybackError::Spirc(e)) => {
+            println!("[投票] ⚠️ 無法{}: {:?}", tr!(Language::ZhTw, kind.label()), e);
+            ctx.say(tr!(lang, "vote-failed", action = action.as_str())).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 投票跳過目前的歌曲
+#[poise::command(slash_command, guild_only, custom_data = Capability::Control)]
+pub async fn voteskip(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    run_vote(ctx, VoteKind::Skip).await
+}
+
+/// 投票暫停播放
+#[poise::command(slash_command, guild_only, custom_data = Capability::Control)]
+pub async fn votepause(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    run_vote(ctx, VoteKind::Pause).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users(ids: &[u64]) -> Vec<UserId> {
+        ids.iter().map(|id| UserId::new(*id)).collect()
+    }
+
+    #[test]
+    fn required_votes_rounds_up() {
+        assert_eq!(required_votes(4, 50), 2);
+        assert_eq!(required_votes(5, 50), 3);
+        assert_eq!(required_votes(3, 100), 3);
+        assert_eq!(required_votes(10, 1), 1);
+    }
+
+    #[test]
+    fn required_votes_is_at_least_one() {
+        assert_eq!(required_votes(0, 50), 1);
+        assert_eq!(required_votes(1, 1), 1);
+    }
+
+    #[test]
+    fn count_only_includes_current_listeners() {
+        let guild = GuildId::new(1);
+        let mut votes = VoteState::new();
+        assert!(votes.cast(guild, VoteKind::Skip, UserId::new(10)));
+        assert!(!votes.cast(guild, VoteKind::Skip, UserId::new(10)));
+        votes.cast(guild, VoteKind::Skip, UserId::new(11));
+        votes.cast(guild, VoteKind::Pause, UserId::new(12));
+
+        // 11 已經離開頻道
+        assert_eq!(votes.count(guild, VoteKind::Skip, &users(&[10, 12])), 1);
+        assert_eq!(votes.count(guild, VoteKind::Pause, &users(&[10, 12])), 1);
+        assert_eq!(votes.count(GuildId::new(2), VoteKind::Skip, &users(&[10])), 0);
+    }
+
+    #[test]
+    fn reset_and_clear_remove_votes() {
+        let guild = GuildId::new(1);
+        let mut votes = VoteState::new();
+        votes.cast(guild, VoteKind::Skip, UserId::new(10));
+        votes.cast(guild, VoteKind::Pause, UserId::new(10));
+
+        votes.clear(guild, VoteKind::Skip);
+        assert_eq!(votes.count(guild, VoteKind::Skip, &users(&[10])), 0);
+        assert_eq!(votes.count(guild, VoteKind::Pause, &users(&[10])), 1);
+
+        votes.reset();
+        assert_eq!(votes.count(guild, VoteKind::Pause, &users(&[10])), 0);
+    }
+}
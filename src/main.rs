@@ -5,19 +5,51 @@ use lib::config::Config;
 use songbird::{SerenityInit};
 
 mod lib {
+    pub mod announce;
+    pub mod audio_cache;
+    pub mod auth;
     pub mod config;
+    pub mod connect;
     pub mod player;
+    pub mod history;
+    pub mod i18n;
+    pub mod idle;
+    pub mod metadata;
+    pub mod permissions;
+    pub mod quiz;
+    pub mod quiz_admin;
+    pub mod quiz_generator;
+    pub mod queue;
+    pub mod scores;
+    pub mod settings;
+    pub mod shutdown;
+    pub mod stage;
+    pub mod storage;
+    pub mod vote;
+    pub mod voice;
 }
 
 use figment::error::Kind::MissingField;
+use lib::announce::Announcement;
+use lib::audio_cache::AudioCacheStats;
+use lib::connect::{ConnectHandle, ConnectState};
+use lib::history::HistoryStore;
+use lib::i18n::tr;
+use lib::idle::IdleState;
+use lib::metadata::MetadataCache;
 use lib::player::SpotifyPlayer;
-use librespot::core::Error as LibrespotError;
+use lib::quiz::QuizState;
+use lib::quiz_generator::{MetadataQuestionGenerator, QuestionGenerator};
+use lib::scores::ScoreStore;
+use lib::settings::SettingsStore;
+use lib::stage::StageState;
+use lib::vote::VoteState;
 use librespot::playback::config::Bitrate;
 use librespot::playback::player::PlayerEvent;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use serenity::all::GatewayIntents;
+use serenity::all::{GatewayIntents, StageInstance};
 use serenity::{
     async_trait,
     client::{Context, EventHandler},
@@ -28,18 +60,45 @@ use serenity::{
 type Error = Box<dyn std::error::Error + Send + Sync>;
 type PoiseContext<'a> = poise::Context<'a, Data, Error>;
 
+/// 以只有使用者自己看得到的訊息回覆
+async fn say_ephemeral(ctx: PoiseContext<'_>, content: impl Into<String>) -> Result<(), Error> {
+    ctx.send(poise::CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
 // 應用數據結構
+#[derive(Clone)]
 pub struct Data {
     pub config: Config,
     pub player: Arc<Mutex<SpotifyPlayer>>,
-
+    // Spirc 的啟用與停用都透過這個 actor，避免同時重建
+    pub connect: ConnectHandle,
+    pub history: Arc<Mutex<HistoryStore>>,
+    // 目前播放曲目的 Spotify Track ID（base62）
+    pub now_playing: Arc<Mutex<Option<String>>>,
+    pub quiz: Arc<QuizState>,
+    pub quiz_generator: Arc<dyn QuestionGenerator>,
+    pub scores: Arc<Mutex<ScoreStore>>,
+    pub idle: Arc<Mutex<IdleState>>,
+    pub stage: Arc<Mutex<StageState>>,
+    pub metadata: Arc<MetadataCache>,
+    pub votes: Arc<Mutex<VoteState>>,
+    pub settings: Arc<Mutex<SettingsStore>>,
+    // 音訊檔案快取的命中統計
+    pub audio_cache: Arc<Mutex<AudioCacheStats>>,
 }
 
 // 新增一個共享的事件處理器狀態
 struct EventHandlerState {
-    handle: Option<tokio::task::JoinHandle<()>>,
+    // 依照 Connect 的狀態替換 Spotify 事件處理器的 task
+    event_supervisor: Option<tokio::task::JoinHandle<()>>,
+    idle_monitor: Option<tokio::task::JoinHandle<()>>,
 }
 
+// 使用者離開語音頻道後，等待其加入其他伺服器頻道的時間
+const USER_MOVE_GRACE: std::time::Duration = std::time::Duration::from_secs(2);
+
 // 用於在 serenity 的 TypeMap 中存儲 Poise 數據
 struct PoiseDataKey;
 impl serenity::prelude::TypeMapKey for PoiseDataKey {
@@ -54,9 +113,21 @@ impl EventHandler for Handler {
         let data = ctx.data.read().await;
         let (poise_data, event_handler_state) = data.get::<PoiseDataKey>().unwrap();
 
-        let player = poise_data.player.clone();
         let config = &poise_data.config;
 
+        // 閒置監控與事件處理器的監督只需要啟動一次
+        {
+            let mut handler_state = event_handler_state.lock().await;
+            if handler_state.idle_monitor.is_none() {
+                handler_state.idle_monitor =
+                    Some(lib::idle::spawn_idle_monitor(ctx.clone(), poise_data.clone()));
+            }
+            if handler_state.event_supervisor.is_none() {
+                handler_state.event_supervisor =
+                    Some(tokio::spawn(supervise_spotify_events(ctx.clone(), poise_data.clone())));
+            }
+        }
+
         // 處理機器人啟動時使用者已在語音頻道中的情況
         let user_in_voice = guilds.iter().any(|guild_id| {
             if let Some(guild) = ctx.cache.guild(*guild_id) {
@@ -72,30 +143,18 @@ impl EventHandler for Handler {
 
         if user_in_voice {
             println!("檢測到使用者在語音頻道中,準備啟用 Spotify Connect...");
-
-            // 啟用 connect 並檢查是否重新創建了 Player
-            let player_recreated = player.lock().await.enable_connect().await;
-
-            if player_recreated {
-                println!("Player 已重新創建，設置初始事件處理器...");
-
-                // 設置初始事件處理器
-                let c = ctx.clone();
-                let player_clone = player.clone();
-
-                let mut handler_state = event_handler_state.lock().await;
-                let new_handle = tokio::spawn(async move {
-                    handle_spotify_events(c, player_clone).await;
-                });
-                handler_state.handle = Some(new_handle);
-                println!("✓ 初始事件處理器已設置");
-            }
+            poise_data.connect.enable().await;
         } else {
-            player.lock().await.disable_connect().await; // <--- 禁用 Connect
+            poise_data.connect.disable().await; // <--- 禁用 Connect
             println!("使用者不在語音頻道中,不啟用 Spotify Connect");
         }
     }
 
+    async fn stage_instance_delete(&self, ctx: Context, stage_instance: StageInstance) {
+        let poise_data = poise_data_of(&ctx).await;
+        lib::stage::on_stage_ended(&ctx, &poise_data, &stage_instance).await;
+    }
+
     async fn ready(&self, _ctx: Context, ready: Ready) {
         println!("就緒！");
         println!(
@@ -105,78 +164,84 @@ impl EventHandler for Handler {
     }
 
     async fn voice_state_update(&self, ctx: Context, old: Option<VoiceState>, new: VoiceState) {
-        let data = ctx.data.read().await;
-        let (poise_data, event_handler_state) = data.get::<PoiseDataKey>().unwrap();
+        // 複製一份共用狀態，不在下面的等待期間持有 ctx.data 的讀取鎖
+        let poise_data = &poise_data_of(&ctx).await;
         let config = &poise_data.config;
 
         if new.user_id.to_string() != config.discord_user_id.to_string() {
+            // 其他人進出機器人所在的頻道時，檢查是否還有人在聽
+            // （目標使用者的移動由下方跟隨邏輯處理）
+            if let Some(guild_id) = new.guild_id.or(old.as_ref().and_then(|o| o.guild_id)) {
+                lib::idle::update_listeners(&ctx, poise_data, guild_id).await;
+            }
             return;
         }
 
         println!("檢測到目標使用者的語音狀態變更");
 
-        let player = &poise_data.player;
-        let manager = songbird::get(&ctx)
-            .await
-            .expect("在初始化時已放入 Songbird 語音客戶端。");
+        let Some(manager) = songbird::get(&ctx).await else {
+            println!("⚠️ 找不到 Songbird 語音客戶端");
+            return;
+        };
 
         // 使用者加入語音頻道
         if old.is_none() || old.as_ref().and_then(|o| o.channel_id).is_none() {
-            if new.channel_id.is_some() {
+            if let (Some(guild_id), Some(channel_id)) = (new.guild_id, new.channel_id) {
+                // 跨伺服器移動：機器人還在其他伺服器的語音頻道中，直接跟過去
+                let in_other_guild = ctx
+                    .cache
+                    .guilds()
+                    .into_iter()
+                    .any(|other| other != guild_id && manager.get(other).is_some());
+                if in_other_guild && poise_data.connect.state().is_enabled() {
+                    println!("使用者移動到其他伺服器的語音頻道，跟著移動...");
+                    lib::voice::follow_user(&ctx, poise_data, guild_id, channel_id).await;
+                    return;
+                }
+
                 println!("使用者加入語音頻道，啟用 Spotify Connect...");
 
                 // 只啟用 Spotify Connect，不加入頻道
                 // Bot 會在收到 SessionConnected + Playing 事件後才加入
-                let player_recreated = player.lock().await.enable_connect().await;
-
-                if player_recreated {
-                    println!("Player 已重新創建，設置事件處理器...");
-
-                    let mut handler_state = event_handler_state.lock().await;
-                    if let Some(handle) = handler_state.handle.take() {
-                        handle.abort();
-                    }
-
-                    let c = ctx.clone();
-                    let player_clone = player.clone();
-
-                    let new_handle = tokio::spawn(async move {
-                        handle_spotify_events(c, player_clone).await;
-                    });
-
-                    handler_state.handle = Some(new_handle);
-                }
+                poise_data.connect.enable().await;
             }
             return;
         }
 
         // 使用者離開語音頻道
         if old.as_ref().and_then(|o| o.channel_id).is_some() && new.channel_id.is_none() {
+            // 跨伺服器移動時會先收到離開事件，稍等一下確認使用者是否已在其他頻道
+            tokio::time::sleep(USER_MOVE_GRACE).await;
+            if lib::voice::user_voice_channel(&ctx, new.user_id).is_some() {
+                println!("使用者已移動到其他伺服器的語音頻道，保持 Spotify Connect");
+                return;
+            }
+
             println!("使用者離開語音頻道，停用 Spotify Connect...");
 
             // 停用 Spotify Connect（喇叭會消失）
-            player.lock().await.disable_connect().await;
+            poise_data.connect.disable().await;
 
             // 離開語音頻道
             if let Some(guild_id) = new.guild_id {
-                let _ = manager.remove(guild_id).await;
+                lib::voice::leave(&ctx, poise_data, guild_id).await;
             }
 
             ctx.set_presence(None, user::OnlineStatus::Online);
             return;
         }
 
-        // 使用者切換頻道 - 讓 Playing 事件處理
+        // 使用者在同一個伺服器內切換頻道 - 跟著移動並繼續播放
         if old.as_ref().and_then(|o| o.channel_id).is_some() && new.channel_id.is_some() {
             let old_channel = old.as_ref().and_then(|o| o.channel_id);
             let new_channel = new.channel_id;
 
             if old_channel != new_channel {
-                println!("使用者切換頻道: {:?} -> {:?}，等待 Spotify 事件...", old_channel, new_channel);
-                if let Some(ref spirc) = player.lock().await.spirc {
-                    println!("⏸️ 暫停播放（使用者切換頻道）...");
-                    if let Err(e) = spirc.pause() {
-                        println!("⚠️ 暫停失敗: {:?}", e);
+                println!("使用者切換頻道: {:?} -> {:?}，跟著移動...", old_channel, new_channel);
+                if let (Some(guild_id), Some(channel_id)) = (new.guild_id, new_channel) {
+                    // 機器人還沒加入語音頻道時，等 Playing 事件再加入
+                    if manager.get(guild_id).is_some() {
+                        lib::voice::follow_user(&ctx, poise_data, guild_id, channel_id).await;
                     }
                 }
             }
@@ -184,6 +249,31 @@ impl EventHandler for Handler {
     }
 }
 
+// 每次 Spotify Connect 啟用後換上新的事件處理器，同一時間只會有一個在執行
+async fn supervise_spotify_events(ctx: Context, data: Data) {
+    let mut state = data.connect.subscribe();
+    let mut handler: Option<tokio::task::JoinHandle<()>> = None;
+    let mut current_generation = None;
+
+    loop {
+        let connect_state = *state.borrow_and_update();
+        if let ConnectState::Enabled { generation } = connect_state {
+            if current_generation != Some(generation) {
+                if let Some(handle) = handler.take() {
+                    handle.abort();
+                }
+                println!("Spotify Connect 已啟用（第 {} 次），設置事件處理器...", generation);
+                handler = Some(tokio::spawn(handle_spotify_events(ctx.clone(), data.player.clone())));
+                current_generation = Some(generation);
+            }
+        }
+
+        if state.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
 // 獨立的函數處理 Spotify 事件
 // 修改 handle_spotify_events 函數
 
@@ -221,28 +311,24 @@ async fn handle_spotify_events(ctx: Context, player: Arc<Mutex<SpotifyPlayer>>)
                 let (poise_data, _) = data.get::<PoiseDataKey>().unwrap();
                 let config = &poise_data.config;
 
-                let manager = songbird::get(&ctx)
-                    .await
-                    .expect("在初始化時已放入 Songbird 語音客戶端。");
+                let Some(manager) = songbird::get(&ctx).await else {
+                    println!("⚠️ 找不到 Songbird 語音客戶端");
+                    continue;
+                };
 
                 // 找到使用者所在的語音頻道
-                if let Some((guild_id, channel_id)) = ctx.cache.guilds().iter().find_map(|gid| {
-                    ctx.cache
-                        .guild(gid)
-                        .and_then(|guild| {
-                            guild
-                                .voice_states
-                                .get(&config.discord_user_id.into())
-                                .and_then(|state| state.channel_id.map(|ch| (gid.to_owned(), ch)))
-                        })
-                }) {
+                if let Some((guild_id, channel_id)) =
+                    lib::voice::user_voice_channel(&ctx, config.discord_user_id.into())
+                {
                     println!("📍 使用者在語音頻道中，準備加入...");
 
                     // 加入語音頻道（但還不播放，等 Playing 事件）
                     if manager.get(guild_id).is_none() {
                         match manager.join(guild_id, channel_id).await {
-                            Ok(_) => {
+                            Ok(call) => {
                                 println!("✓ Bot 已加入語音頻道（等待播放）");
+                                lib::voice::register_driver_events(&ctx, poise_data, guild_id, &call).await;
+                                lib::stage::on_joined(&ctx, poise_data, guild_id, channel_id).await;
                             }
                             Err(e) => {
                                 println!("✗ 加入語音頻道失敗: {:?}", e);
@@ -262,16 +348,20 @@ async fn handle_spotify_events(ctx: Context, player: Arc<Mutex<SpotifyPlayer>>)
 
                 // 清除 Discord 狀態
                 ctx.set_presence(None, user::OnlineStatus::Online);
+                let poise_data = poise_data_of(&ctx).await;
+                *poise_data.now_playing.lock().await = None;
+                poise_data.idle.lock().await.on_idle();
 
                 // 離開所有語音頻道
-                let manager = songbird::get(&ctx)
-                    .await
-                    .expect("在初始化時已放入 Songbird 語音客戶端。");
+                let Some(manager) = songbird::get(&ctx).await else {
+                    println!("⚠️ 找不到 Songbird 語音客戶端");
+                    continue;
+                };
 
                 for guild_id in ctx.cache.guilds() {
                     if manager.get(guild_id).is_some() {
                         println!("👋 離開語音頻道 (Guild: {:?})", guild_id);
-                        let _ = manager.remove(guild_id).await;
+                        lib::voice::leave(&ctx, &poise_data, guild_id).await;
                     }
                 }
             }
@@ -280,63 +370,90 @@ async fn handle_spotify_events(ctx: Context, player: Arc<Mutex<SpotifyPlayer>>)
             PlayerEvent::Stopped { .. } => {
                 println!("⏹️ Spotify 已停止播放");
                 ctx.set_presence(None, user::OnlineStatus::Online);
-                // 不離開頻道，等待 SessionDisconnected 事件
-                println!("ℹ️ 保持在語音頻道中，等待 Spotify 斷線或下一首...");
+                let poise_data = poise_data_of(&ctx).await;
+                poise_data.history.lock().await.finish_current();
+                *poise_data.now_playing.lock().await = None;
+                poise_data.idle.lock().await.on_idle();
+                // 不離開頻道，等待 SessionDisconnected 事件或閒置逾時
+                println!("ℹ️ 保持在語音頻道中，等待 Spotify 斷線、下一首或閒置逾時...");
             }
 
-            PlayerEvent::Loading { .. } => {
+            PlayerEvent::Loading { track_id, .. } => {
                 println!("🔄 Spotify 正在載入音樂, 重設音訊接收器...");
-                player.lock().await.emitted_sink.reset();
+                let session = {
+                    let mut player = player.lock().await;
+                    player.emitted_sink.reset();
+                    player.session.clone()
+                };
                 println!("✓ 音訊接收器已重設");
+                // 在背景檢查快取，不延遲播放
+                tokio::spawn(lib::audio_cache::on_loading(poise_data_of(&ctx).await, session, *track_id));
                 continue;
             }
 
             PlayerEvent::Playing { track_id, .. } => {
                 println!("▶️ Spotify 開始播放");
+                let poise_data = poise_data_of(&ctx).await;
+                // 暫停後繼續播放同一首歌時不算換歌
+                let is_new_track = {
+                    let mut now_playing = poise_data.now_playing.lock().await;
+                    let track = track_id.to_base62().ok();
+                    let changed = *now_playing != track;
+                    *now_playing = track;
+                    changed
+                };
+                poise_data.idle.lock().await.on_playing();
+                // 每首歌重新計票
+                if is_new_track {
+                    poise_data.votes.lock().await.reset();
+                }
 
                 // ... (保持原有的 Playing 處理邏輯，但移除加入頻道的部分)
                 // 因為已經在 SessionConnected 時加入了
 
                 // 設置 Discord 活動狀態
-                let mut attempts = 0;
-                let max_attempts = 3;
-                let mut track_result: Result<librespot::metadata::Track, LibrespotError> =
-                    Err(LibrespotError::deadline_exceeded("Initial attempt"));
-
-                while attempts < max_attempts {
-                    track_result = librespot::metadata::Metadata::get(
-                        &player.lock().await.session,
-                        track_id,
-                    ).await;
-
-                    if track_result.is_ok() {
-                        break;
-                    }
-
-                    attempts += 1;
-                    if attempts < max_attempts {
-                        println!("⚠️ 無法獲取 Metadata (嘗試 {}/{}), 1秒後重試...", attempts, max_attempts);
-                        tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-                    }
+                // 只在複製 Session 時鎖住 player，查詢 Metadata 期間不持有鎖
+                let session = player.lock().await.session.clone();
+                // 依照 SpotifyId 的種類查詢曲目或 Podcast / 有聲書單集
+                let item_result = poise_data.metadata.playable(&session, track_id).await;
+                if let Err(ref e) = item_result {
+                    println!("⚠️ 無法獲取 Metadata: {:?}", e);
                 }
 
-                if let Ok(track) = track_result {
-                    let mut artist_name = "Unknown Artist".to_string();
-                    if let Some(artist_id) = track.artists.first() {
-                        let artist_result: Result<librespot::metadata::Artist, LibrespotError> =
-                            librespot::metadata::Metadata::get(
-                                &player.lock().await.session,
-                                &artist_id.id,
-                            ).await;
-
-                        if let Ok(artist) = artist_result {
-                            artist_name = artist.name;
+                if let Ok(item) = item_result {
+                    // 單集的 artists 是節目名稱
+                    let artist_name = item
+                        .artists
+                        .first()
+                        .cloned()
+                        .unwrap_or_else(|| "Unknown Artist".to_string());
+
+                    // 記錄到播放歷史
+                    poise_data
+                        .history
+                        .lock()
+                        .await
+                        .on_playing(item.kind, &item.id, &item.title, &item.artists);
+
+                    let listening_to = format!("{}: {}", artist_name, item.title);
+                    println!("{} 正在播放: {}", item.kind.emoji(), listening_to);
+                    lib::stage::update_topic(&ctx, &poise_data, &listening_to).await;
+
+                    if is_new_track {
+                        let user_id = poise_data.config.discord_user_id.into();
+                        if let Some((guild_id, _)) = lib::voice::user_voice_channel(&ctx, user_id) {
+                            let announcement = Announcement {
+                                kind: item.kind,
+                                title: item.title.clone(),
+                                artists: item.artists.join(", "),
+                                album: item.album.clone(),
+                                url: item.url(),
+                                duration_ms: item.duration_ms,
+                            };
+                            lib::announce::announce(&ctx, &poise_data, guild_id, &announcement).await;
                         }
                     }
 
-                    let listening_to = format!("{}: {}", artist_name, track.name);
-                    println!("🎵 正在播放: {}", listening_to);
-
                     use serenity::all::{ActivityData, ActivityType};
                     let activity = ActivityData {
                         name: listening_to,
@@ -358,89 +475,22 @@ async fn handle_spotify_events(ctx: Context, player: Arc<Mutex<SpotifyPlayer>>)
                 }
 
                 // 確保 Bot 在正確的頻道並播放音訊
-                let manager = songbird::get(&ctx)
-                    .await
-                    .expect("在初始化時已放入 Songbird 語音客戶端。");
-
-                let data = ctx.data.read().await;
-                let (poise_data, _) = data.get::<PoiseDataKey>().unwrap();
-                let config = &poise_data.config;
-
-                let Some((guild_id, channel_id)) = ctx.cache.guilds().iter().find_map(|gid| {
-                    ctx.cache
-                        .guild(gid)
-                        .and_then(|guild| {
-                            guild
-                                .voice_states
-                                .get(&config.discord_user_id.into())
-                                .and_then(|state| state.channel_id.map(|ch| (gid.to_owned(), ch)))
-                        })
-                }) else {
+                let user_id = poise_data.config.discord_user_id.into();
+                let Some((guild_id, channel_id)) = lib::voice::user_voice_channel(&ctx, user_id) else {
                     println!("⚠️ 無法在語音頻道中找到使用者。");
                     continue;
                 };
 
-                // 檢查是否需要加入或切換頻道
-                let handler_lock = if let Some(h) = manager.get(guild_id) {
-                    // 檢查是否在正確頻道
-                    let handler = h.lock().await;
-                    let current_channel = handler.current_channel();
-                    drop(handler);
-
-                    let songbird_channel_id: songbird::id::ChannelId = channel_id.into();
-                    if current_channel != Some(songbird_channel_id) {
-                        println!("🔄 切換到使用者所在頻道...");
-                        let _ = manager.remove(guild_id).await;
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-                        match manager.join(guild_id, channel_id).await {
-                            Ok(_) => println!("✓ 成功切換頻道"),
-                            Err(e) => {
-                                println!("✗ 切換頻道失敗: {:?}", e);
-                                continue;
-                            }
-                        }
-                    }
-                    manager.get(guild_id)
-                } else {
-                    // 還沒加入，現在加入
-                    println!("🎤 加入語音頻道...");
-                    match manager.join(guild_id, channel_id).await {
-                        Ok(_) => {
-                            println!("✓ 成功加入語音頻道");
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                        }
-                        Err(e) => {
-                            println!("✗ 加入語音頻道失敗: {:?}", e);
-                            continue;
-                        }
-                    }
-                    manager.get(guild_id)
-                };
-
-                // 播放音訊
-                if let Some(handler_lock) = handler_lock {
-                    let mut handler = handler_lock.lock().await;
-                    handler.stop();
-
-                    println!("🎵 準備音訊源...");
-                    use songbird::input::{Input, RawAdapter};
-                    let source: Input = RawAdapter::new(
-                        player.lock().await.emitted_sink.clone(),
-                        48000,
-                        2,
-                    ).into();
-
-                    handler.set_bitrate(songbird::driver::Bitrate::Auto);
-                    let track_handle = handler.play_input(source);
-                    println!("✓ 開始播放音訊，軌道 UUID: {:?}", track_handle.uuid());
-                }
+                lib::voice::join_and_play(&ctx, &poise_data, guild_id, channel_id).await;
             }
 
             PlayerEvent::Paused { .. } => {
                 println!("⏸️ Spotify 已暫停");
                 ctx.set_presence(None, user::OnlineStatus::Online);
-                // 保持在頻道中
+                let poise_data = poise_data_of(&ctx).await;
+                poise_data.history.lock().await.on_paused();
+                poise_data.idle.lock().await.on_idle();
+                // 保持在頻道中，超過閒置時間後才離開
             }
 
             PlayerEvent::Unavailable { track_id, .. } => {
@@ -449,6 +499,7 @@ async fn handle_spotify_events(ctx: Context, player: Arc<Mutex<SpotifyPlayer>>)
 
             PlayerEvent::EndOfTrack { track_id, .. } => {
                 println!("✅ 曲目播放完畢: {:?}", track_id);
+                poise_data_of(&ctx).await.history.lock().await.finish_current();
             }
 
             _ => {}
@@ -458,11 +509,19 @@ async fn handle_spotify_events(ctx: Context, player: Arc<Mutex<SpotifyPlayer>>)
     println!("事件處理器已結束");
 }
 
+// 從 TypeMap 取得應用數據
+async fn poise_data_of(ctx: &Context) -> Data {
+    let data = ctx.data.read().await;
+    let (poise_data, _) = data.get::<PoiseDataKey>().unwrap();
+    poise_data.clone()
+}
+
 // Poise 命令函數
 /// 顯示機器人資訊
 #[poise::command(slash_command, prefix_command)]
 async fn info(ctx: PoiseContext<'_>) -> Result<(), Error> {
-    ctx.say("這是一個 Spotify Discord 機器人!").await?;
+    let lang = lib::i18n::language(ctx).await;
+    ctx.say(tr!(lang, "info-reply")).await?;
     Ok(())
 }
 
@@ -512,28 +571,94 @@ async fn main() {
         None
     };
 
-    let player = Arc::new(Mutex::new(
-        SpotifyPlayer::new(
-            Bitrate::Bitrate320,
-            cache_dir,
-            config.spotify_bot_autoplay,
-            config.spotify_device_name.clone(),
-        )
-            .await,
-    ));
+    let history = Arc::new(Mutex::new(HistoryStore::load(cache_dir.as_deref())));
+    let quiz = Arc::new(QuizState::load(cache_dir.as_deref()));
+    let scores = Arc::new(Mutex::new(ScoreStore::load(cache_dir.as_deref())));
+    let settings = Arc::new(Mutex::new(SettingsStore::load(cache_dir.as_deref())));
+    let metadata = Arc::new(MetadataCache::load(cache_dir.as_deref()));
+
+    // 沒有快取憑證時仍會啟動，等管理員使用 /spotify_login 認證
+    let player = match SpotifyPlayer::new(
+        Bitrate::Bitrate320,
+        cache_dir,
+        config.audio_cache_limit(),
+        config.spotify_bot_autoplay,
+        config.spotify_device_name.clone(),
+    )
+    .await
+    {
+        Ok(player) => Arc::new(Mutex::new(player)),
+        Err(e) => {
+            println!("無法建立 Spotify 播放器：{e:?}");
+            exit(1)
+        }
+    };
+
+    // Spirc 生命週期的 actor
+    let connect = ConnectHandle::spawn(player.clone());
 
     // 創建事件處理器狀態
-    let event_handler_state = Arc::new(Mutex::new(EventHandlerState { handle: None }));
+    let event_handler_state = Arc::new(Mutex::new(EventHandlerState {
+        event_supervisor: None,
+        idle_monitor: None,
+    }));
+
+    let data = Data {
+        config: config.clone(),
+        player,
+        connect,
+        history,
+        now_playing: Arc::new(Mutex::new(None)),
+        quiz,
+        quiz_generator: Arc::new(MetadataQuestionGenerator),
+        scores,
+        idle: Arc::new(Mutex::new(IdleState::new())),
+        stage: Arc::new(Mutex::new(StageState::new())),
+        metadata,
+        votes: Arc::new(Mutex::new(VoteState::new())),
+        settings,
+        audio_cache: Arc::new(Mutex::new(AudioCacheStats::default())),
+    };
 
     // 克隆用於閉包的變數
-    let player_for_framework = player.clone();
-    let config_for_framework = config.clone();
+    let data_for_framework = data.clone();
     let discord_token = config.discord_token.clone();
 
+    let mut commands = vec![
+        info(),
+        help(),
+        lib::history::history(),
+        lib::history::history_export(),
+        lib::announce::announce_command(),
+        lib::auth::spotify_login(),
+        lib::audio_cache::cache(),
+        lib::permissions::permissions(),
+        lib::settings::settings(),
+        lib::queue::search(),
+        lib::queue::queue(),
+        lib::vote::voteskip(),
+        lib::vote::votepause(),
+        lib::quiz::quiz(),
+        lib::quiz_admin::quiz_import(),
+        lib::quiz_admin::quiz_export(),
+        lib::quiz_admin::quiz_add(),
+        lib::quiz_admin::quiz_edit(),
+        lib::quiz_admin::quiz_remove(),
+        lib::quiz_admin::quiz_report(),
+        lib::scores::leaderboard(),
+        lib::scores::stats(),
+        lib::scores::stats_export(),
+        lib::scores::stats_reset(),
+    ];
+    // 依照 translations/ 的訊息目錄設定指令的名稱與說明
+    lib::i18n::apply_translations(&mut commands, config.language);
+
     // 創建 Poise 框架
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![info(), help()],
+            commands,
+            // 依照指令標記的能力與伺服器的身分組設定檢查權限
+            command_check: Some(|ctx| Box::pin(lib::permissions::command_check(ctx))),
             event_handler: |_ctx, _event, _framework, _data| {
                 Box::pin(async move {
                     Ok(())
@@ -544,11 +669,14 @@ async fn main() {
         .setup(move |ctx, _ready, framework| {
             Box::pin(async move {
                 poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-
-                Ok(Data {
-                    config: config_for_framework,
-                    player: player_for_framework,
-                })
+                // 收到 SIGTERM/SIGINT 時關閉 Spotify Connect 並離開語音頻道
+                lib::shutdown::spawn_signal_handler(
+                    ctx.clone(),
+                    data_for_framework.clone(),
+                    framework.shard_manager().clone(),
+                );
+
+                Ok(data_for_framework)
             })
         })
         .build();
@@ -564,14 +692,8 @@ async fn main() {
 
     // 將 Data 和事件處理器狀態放入 serenity 的 TypeMap 中
     {
-        let mut data = client.data.write().await;
-        data.insert::<PoiseDataKey>((
-            Data {
-                config: config.clone(),
-                player: player.clone(),
-            },
-            event_handler_state,
-        ));
+        let mut type_map = client.data.write().await;
+        type_map.insert::<PoiseDataKey>((data, event_handler_state));
     }
 
     let _ = client
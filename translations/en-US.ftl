@@ -0,0 +1,386 @@
+# Aoede English message catalog
+#
+# Commands: the message ID is the command's qualified name (subcommands joined with -),
+# .description is the description, .<param> and .<param>-description are the parameter name and description
+
+## General
+
+info = info
+    .description = Show information about the bot
+info-reply = This is a Spotify Discord bot!
+help = help
+    .description = Show help
+    .command = command
+    .command-description = Show help for a specific command
+
+## History
+
+history = history
+    .description = Show recently played tracks
+    .n = n
+    .n-description = Number of entries to show (default 10, max 25)
+history_export = history_export
+    .description = Export the full play history
+    .format = format
+    .format-description = Export format (default CSV)
+history-empty = Nothing has been played yet
+history-title = 🎧 **Last { $count } tracks played**
+history-listened = listened { $duration }
+history-export-done = 📄 Play history export
+
+## Announcements
+
+announce = announce
+    .description = Turn now-playing announcements on or off
+    .enabled = enabled
+    .enabled-description = Post an announcement when the track changes
+    .channel = channel
+    .channel-description = Announcement channel (defaults to the current channel)
+announce-enabled = ✓ Track changes will be announced in { $channel }
+announce-disabled = ✓ Now-playing announcements are off
+
+## Idle
+
+duration-minutes = { $minutes ->
+    [one] { $minutes } minute
+   *[other] { $minutes } minutes
+}
+duration-seconds = { $seconds ->
+    [one] { $seconds } second
+   *[other] { $seconds } seconds
+}
+idle-left = 👋 Idle for { $duration }, leaving the voice channel
+idle-reason-alone = Nobody else is in the channel
+idle-reason-paused = Playback is paused
+idle-warning = ⚠️ { $reason }, leaving the voice channel in { $duration }
+
+## Voice connection
+
+voice-disconnected = ⚠️ Voice connection lost ({ $reason }), rejoining { $channel }
+voice-reconnected = ✓ Voice connection recovered
+voice-rejoined = ✓ Rejoined { $channel } (attempt { $attempt })
+voice-rejoin-failed = ✗ Failed to rejoin { $channel } after { $attempts } attempts, giving up
+
+## Search and queue
+
+connect-not-enabled = Spotify Connect isn't enabled yet, join a voice channel first
+search = search
+    .description = Search Spotify tracks and pick one from a menu to add to the queue
+    .query = query
+    .query-description = Search terms
+search-placeholder = Choose a track to add to the queue
+search-failed = ✗ Search failed, please try again later
+search-no-results = No results for “{ $query }”
+search-results = 🔍 Results for “{ $query }”:
+search-not-yours = Only the person who searched can pick a track, use /search yourself
+search-timeout = 🔍 The search for “{ $query }” timed out
+queue = queue
+    .description = Playback queue
+queue-add = add
+    .description = Add a track to the queue by Spotify URL or URI
+    .url = url
+    .url-description = URL or URI of a Spotify track or episode
+queue-list = list
+    .description = Show the upcoming tracks
+queue-added-track = ✓ Added **{ $title }** - { $artists } to the queue
+queue-added-url = ✓ Added <{ $url }> to the queue
+queue-add-failed = ✗ Couldn't add to the queue, make sure Spotify is playing on this device
+queue-invalid-url = ✗ Unrecognised Spotify URL, paste a link to a track or episode
+queue-list-failed = ✗ Couldn't fetch the queue, please try again later
+queue-title = 🎶 Queue
+queue-nothing-playing = Nothing is playing right now
+queue-empty = The queue is empty
+queue-more = …and { $count } more
+
+## Voting
+
+voteskip = voteskip
+    .description = Vote to skip the current track
+votepause = votepause
+    .description = Vote to pause playback
+vote-action-skip = skip
+vote-action-pause = pause
+vote-bot-not-in-voice = The bot isn't in a voice channel on this server
+vote-listeners-only = Only listeners in { $channel } can vote
+vote-cast = 🗳️ { $user } voted to { $action }: { $count }/{ $required }
+vote-already-cast = You've already voted ({ $count }/{ $required })
+vote-owner-bypass = { $emoji } { $user } used owner override to { $action } this track
+vote-passed = ✓ Vote passed ({ $count }/{ $required }), going to { $action } this track
+vote-failed = ✗ Couldn't { $action }, please try again later
+
+## Permissions
+
+permissions = permissions
+    .description = Manage which roles can use which commands
+permissions-grant = grant
+    .description = Give a role a capability
+    .capability = capability
+    .capability-description = Capability
+    .role = role
+    .role-description = Role
+permissions-revoke = revoke
+    .description = Remove a capability from a role
+    .capability = capability
+    .capability-description = Capability
+    .role = role
+    .role-description = Role
+permissions-list = list
+    .description = List the roles assigned to each capability
+permission-guild-only = ✗ This command can only be used in a server
+permission-owner-only = ✗ This command affects every server, only the bot's owner can use it
+permission-denied-managers = ✗ This command needs the `{ $capability }` capability, which is currently limited to server managers
+permission-denied-roles = ✗ This command needs the `{ $capability }` capability, ask an admin for one of these roles: { $roles }
+permission-granted = ✓ { $role } now has the `{ $capability }` capability
+permission-already-granted = { $role } already has the `{ $capability }` capability
+permission-revoked = ✓ Removed the `{ $capability }` capability from { $role }
+permission-not-granted = { $role } doesn't have the `{ $capability }` capability
+permission-everyone = Everyone
+permission-managers = Server managers
+
+## Server settings
+
+settings = settings
+    .description = Server settings
+settings-view = view
+    .description = Show this server's settings
+settings-bitrate = bitrate
+    .description = Set the voice bitrate, leave empty to let Discord decide
+    .kbps = kbps
+    .kbps-description = Bitrate (kbps)
+settings-idle_timeout = idle_timeout
+    .description = How long to stay in voice while paused or alone, leave empty for the default
+    .minutes = minutes
+    .minutes-description = Minutes (0 never leaves automatically)
+settings-idle_warning = idle_warning
+    .description = How long before leaving to post a warning, leave empty for the default
+    .seconds = seconds
+    .seconds-description = Seconds
+settings-language = language
+    .description = Set the language the bot uses in this server
+    .language = language
+    .language-description = Language
+settings-title = ⚙️ Server settings
+settings-field-announce = Now-playing announcements
+settings-field-bitrate = Voice bitrate
+settings-field-language = Language
+settings-field-idle-timeout = Idle timeout
+settings-field-idle-warning = Warning before leaving
+settings-field-permissions = Permissions
+settings-off = Off
+settings-auto = Automatic
+settings-default = { $value } (default)
+settings-unit-kbps = { $value } kbps
+settings-unit-minutes = { $value ->
+    [one] { $value } minute
+   *[other] { $value } minutes
+}
+settings-unit-seconds = { $value ->
+    [one] { $value } second
+   *[other] { $value } seconds
+}
+settings-roles = { $count ->
+    [one] { $count } role (see /permissions list)
+   *[other] { $count } roles (see /permissions list)
+}
+settings-bitrate-set = ✓ Voice bitrate set to { $kbps } kbps
+settings-bitrate-auto = ✓ Voice bitrate is automatic again
+settings-idle-timeout-off = ✓ The bot will no longer leave when idle
+settings-idle-timeout-set = ✓ The bot will leave voice after { $minutes } idle minutes
+settings-idle-warning-set = ✓ A warning will be posted { $seconds } seconds before leaving
+settings-reset = ✓ Reset to the default ({ $value })
+settings-language-set = ✓ Language set to { $language }
+
+## Music quiz
+
+quiz = quiz
+    .description = Start a round of the music quiz based on the current track
+quiz-nothing-playing = Nothing is playing right now
+quiz-join-voice = Join a voice channel before starting a game
+quiz-already-running = A game is already running in this server
+quiz-bank-empty = The question bank is empty, can't start a game
+quiz-invalid-question = This question has invalid data, please tell an admin
+quiz-title = 🎮 Music quiz
+quiz-random-question = ℹ️ No question for the current track, picked a random one
+quiz-question-track = 🎵 Track: “{ $track }”
+quiz-deadline = ⏱️ Answers close { $deadline }
+quiz-already-answered = You've already answered
+quiz-answer-received = Got your answer: { $label }. { $answer }
+quiz-result-title = 🎮 Music quiz - Results
+quiz-result-answer = ✅ Correct answer: { $label }. { $answer }
+    🎵 “{ $track }” - { $artist }
+quiz-result-correct = Correct
+quiz-result-wrong = Wrong
+quiz-result-ranking = ⚡ Fastest answers
+quiz-nobody-correct = Nobody got it right
+quiz-ranking-entry = { $medal } { $user } { $seconds }s · +{ $points } pts
+quiz-feedback-prompt = How was this question?
+quiz-feedback-count = How was this question? 👍 { $likes } · 👎 { $dislikes }
+quiz-feedback-already-voted = You've already rated this question
+quiz-feedback-removed = This question is no longer in the bank
+quiz-generated-anime = Which anime is this the theme song of?
+quiz-generated-artist = Who sings this song?
+quiz-generated-song-name = What's the name of this song?
+
+## Question bank
+
+quiz_import = quiz_import
+    .description = Import a question bank from a JSON file (owner)
+    .file = file
+    .file-description = A file in the quiz_bank.json format
+    .replace = replace
+    .replace-description = Replace the whole bank (merges by id by default)
+quiz_export = quiz_export
+    .description = Export the current question bank (admin)
+quiz_add = quiz_add
+    .description = Add a question (owner)
+    .spotify_track_id = spotify_track_id
+    .spotify_track_id-description = Spotify Track ID
+    .track_name = track_name
+    .track_name-description = Track name
+    .artist = artist
+    .artist-description = Artist
+    .quiz_type = quiz_type
+    .quiz_type-description = Question type
+    .question = question
+    .question-description = Question
+    .correct_answer = correct_answer
+    .correct_answer-description = Correct answer
+    .wrong_1 = wrong_1
+    .wrong_1-description = Wrong option 1
+    .wrong_2 = wrong_2
+    .wrong_2-description = Wrong option 2
+    .wrong_3 = wrong_3
+    .wrong_3-description = Wrong option 3
+    .difficulty = difficulty
+    .difficulty-description = Difficulty (default medium)
+quiz_edit = quiz_edit
+    .description = Edit a question (owner)
+    .id = id
+    .id-description = Question id
+    .question = question
+    .question-description = New question
+    .correct_answer = correct_answer
+    .correct_answer-description = New correct answer
+    .options = options
+    .options-description = Four new options separated by |
+    .quiz_type = quiz_type
+    .quiz_type-description = New question type
+    .difficulty = difficulty
+    .difficulty-description = New difficulty
+quiz_remove = quiz_remove
+    .description = Remove a question from the bank (owner)
+    .id = id
+    .id-description = Question id
+quiz_report = quiz_report
+    .description = Generate a question quality report (admin)
+quiz-import-parse-failed = ✗ Couldn't parse the question bank: { $error }
+quiz-imported = ✓ Imported { $count ->
+    [one] { $count } question
+   *[other] { $count } questions
+}
+quiz-import-invalid = ✗ The question bank is invalid, nothing was imported:
+    { $problems }
+quiz-export-done = 📄 Question bank export
+quiz-added = ✓ Added question `{ $id }`
+quiz-add-invalid = ✗ The question is invalid:
+    { $problems }
+quiz-edited = ✓ Updated question `{ $id }`
+quiz-edit-failed = ✗ Couldn't update the question:
+    { $problems }
+quiz-removed = ✓ Removed question `{ $id }` (“{ $track }”)
+quiz-not-found = ✗ Question `{ $id }` not found
+quiz-report-title = 📋 Question quality report
+quiz-report-clean = ✅ No questions need attention
+quiz-report-entry = `{ $id }` “{ $track }” 👍 { $likes } · 👎 { $dislikes } · { $plays } plays
+    　⚠️ { $flags }
+quiz-report-invalid-entry = `{ $id }` “{ $track }” is not being played
+    　✗ { $problems }
+quiz-report-more = …and { $count } more
+quiz-report-flag-separator = {", "}
+quiz-flag-dislike-ratio = { $percent }% dislikes
+quiz-flag-too-easy = Correct rate too high ({ $percent }%)
+quiz-flag-too-hard = Correct rate too low ({ $percent }%)
+quiz-flag-low-score = Quality score { $score }
+quiz-problem-empty = { $field } can't be empty
+quiz-problem-option-count = options must have exactly { $count } entries
+quiz-problem-blank-option = options can't contain blank entries
+quiz-problem-duplicate-option = options can't contain duplicates
+quiz-problem-answer-not-in-options = correct_answer must be one of the options
+quiz-problem-out-of-range = { $field } must be between 0 and 1
+quiz-problem-duplicate-id = Duplicate id
+quiz-problem-unsupported-version = Unsupported question bank version { $version }
+quiz-report-total = Total questions
+quiz-report-flagged = Need attention
+quiz-report-invalid = Not playable
+
+## Scores
+
+leaderboard = leaderboard
+    .description = Show the music quiz leaderboard
+leaderboard-title = 🏆 Music quiz leaderboard
+leaderboard-entry = `{ $rank }.` { $user } **{ $points }** pts · { $accuracy }% accuracy · best streak { $streak }
+leaderboard-empty = Nobody in this server has played the music quiz yet
+stats = stats
+    .description = Show personal music quiz stats
+    .user = user
+    .user-description = User to look up (defaults to yourself)
+stats_export = stats_export
+    .description = Export this server's music quiz scores (admin)
+stats_reset = stats_reset
+    .description = Reset music quiz scores for this server or one user (admin)
+    .user = user
+    .user-description = Only reset this user (defaults to the whole server)
+stats-empty = { $user } hasn't played the music quiz yet
+stats-title = 📊 { $user }'s music quiz stats
+stats-field-points = Points
+stats-field-games = Games played
+stats-field-accuracy = Accuracy
+stats-field-answers = Correct / wrong
+stats-field-answer-time = Average answer time
+stats-field-streak = Streak (current / best)
+stats-seconds = { $seconds }s
+stats-export-done = 📄 Music quiz score export
+stats-reset-user = ✓ Reset { $user }'s music quiz scores
+stats-reset-guild = ✓ Reset all music quiz scores in this server
+
+## Spotify authentication
+
+spotify_login = spotify_login
+    .description = Re-authenticate the Spotify account through Spotify Connect discovery
+    .force = force
+    .force-description = Re-authenticate even if credentials exist (e.g. to switch accounts)
+auth-in-progress = Authentication is already in progress, pick the bot's device in the Spotify app
+auth-already-authenticated = Spotify is already authenticated, add `force: True` to switch accounts
+auth-started = 🔑 Within 5 minutes, open the Spotify app and pick **{ $device }** from the device list
+auth-failed = ✗ Spotify authentication failed or timed out, run /spotify_login again
+auth-failed-kept = ✗ Spotify authentication failed or timed out, still using the previous account
+auth-done = ✓ Spotify authenticated, join a voice channel to use Spotify Connect
+connect-needs-auth = Spotify isn't authenticated yet, ask the bot's owner to run /spotify_login
+
+## Audio cache
+
+cache = cache
+    .description = Manage the Spotify audio file cache
+cache-stats = stats
+    .description = Show the audio file cache usage and hit rate
+cache-clear = clear
+    .description = Delete every cached audio file (owner)
+cache-title = 💾 Audio file cache
+cache-field-size = Usage
+cache-field-files = Files
+cache-field-hit-rate = Hit rate
+cache-field-evicted = Files evicted by the size limit
+cache-size-limited = { $size } / { $limit } ({ $percent }%)
+cache-size-unlimited = { $size } (no limit)
+cache-hit-rate = { $percent }% ({ $hits } hits, { $misses } misses)
+cache-hit-rate-none = Nothing has played since startup
+cache-footer = Hit rate and evictions are counted since the bot started
+cache-disabled = ✗ No cache directory is configured
+cache-cleared = ✓ Deleted { $count ->
+    [one] { $count } audio file
+   *[other] { $count } audio files
+} ({ $size })
+cache-cleared-partial = ⚠️ Deleted { $count ->
+    [one] { $count } audio file
+   *[other] { $count } audio files
+} ({ $size }), { $failed } could not be deleted
@@ -0,0 +1,362 @@
+# Aoede 繁體中文訊息目錄
+#
+# 指令：訊息 ID 為指令的完整名稱（子指令以 - 連接），
+# .description 為說明，.<參數> 與 .<參數>-description 為參數名稱與說明
+
+## 一般
+
+info = info
+    .description = 顯示機器人資訊
+info-reply = 這是一個 Spotify Discord 機器人!
+help = help
+    .description = 顯示幫助訊息
+    .command = command
+    .command-description = 要獲取幫助的特定命令
+
+## 播放紀錄
+
+history = history
+    .description = 顯示最近播放的曲目
+    .n = n
+    .n-description = 要顯示的筆數（預設 10，最多 25）
+history_export = history_export
+    .description = 匯出全部播放紀錄
+    .format = format
+    .format-description = 匯出格式（預設 CSV）
+history-empty = 目前還沒有播放紀錄
+history-title = 🎧 **最近播放的 { $count } 首曲目**
+history-listened = 聆聽 { $duration }
+history-export-done = 📄 播放紀錄匯出
+
+## 換歌公告
+
+announce = announce
+    .description = 開啟或關閉換歌公告
+    .enabled = enabled
+    .enabled-description = 是否在換歌時發布公告
+    .channel = channel
+    .channel-description = 公告頻道（預設為目前的頻道）
+announce-enabled = ✓ 換歌時會在 { $channel } 發布公告
+announce-disabled = ✓ 已關閉換歌公告
+
+## 閒置
+
+duration-minutes = { $minutes } 分鐘
+duration-seconds = { $seconds } 秒
+idle-left = 👋 已閒置 { $duration }，先離開語音頻道了
+idle-reason-alone = 頻道中沒有其他人
+idle-reason-paused = 音樂已暫停
+idle-warning = ⚠️ { $reason }，將在 { $duration } 後離開語音頻道
+
+## 語音連線
+
+voice-disconnected = ⚠️ 語音連線中斷（{ $reason }），準備重新加入 { $channel }
+voice-reconnected = ✓ 語音連線已自動恢復
+voice-rejoined = ✓ 已重新加入 { $channel }（第 { $attempt } 次嘗試）
+voice-rejoin-failed = ✗ 重新加入 { $channel } 失敗 { $attempts } 次，已放棄
+
+## 搜尋與播放佇列
+
+connect-not-enabled = Spotify Connect 尚未啟用，請先加入語音頻道
+search = search
+    .description = 搜尋 Spotify 曲目，從選單中選擇後加入播放佇列
+    .query = query
+    .query-description = 搜尋關鍵字
+search-placeholder = 選擇要加入佇列的曲目
+search-failed = ✗ 搜尋失敗，請稍後再試
+search-no-results = 找不到「{ $query }」的搜尋結果
+search-results = 🔍 「{ $query }」的搜尋結果：
+search-not-yours = 只有搜尋的人可以選擇曲目，請自己使用 /search
+search-timeout = 🔍 「{ $query }」的搜尋已逾時
+queue = queue
+    .description = 播放佇列
+queue-add = add
+    .description = 以 Spotify 網址或 URI 將曲目加入播放佇列
+    .url = url
+    .url-description = Spotify 曲目或單集的網址或 URI
+queue-list = list
+    .description = 顯示接下來要播放的曲目
+queue-added-track = ✓ 已將 **{ $title }** - { $artists } 加入佇列
+queue-added-url = ✓ 已將 <{ $url }> 加入佇列
+queue-add-failed = ✗ 無法加入佇列，請確認 Spotify 正在這個裝置上播放
+queue-invalid-url = ✗ 無法辨識的 Spotify 網址，請貼上曲目或單集的連結
+queue-list-failed = ✗ 無法取得播放佇列，請稍後再試
+queue-title = 🎶 播放佇列
+queue-nothing-playing = 目前沒有正在播放的曲目
+queue-empty = 佇列是空的
+queue-more = …還有 { $count } 首
+
+## 投票
+
+voteskip = voteskip
+    .description = 投票跳過目前的歌曲
+votepause = votepause
+    .description = 投票暫停播放
+vote-action-skip = 跳過
+vote-action-pause = 暫停
+vote-bot-not-in-voice = 機器人目前不在這個伺服器的語音頻道
+vote-listeners-only = 只有在 { $channel } 中的聽眾可以投票
+vote-cast = 🗳️ { $user } 投票{ $action }：{ $count }/{ $required }
+vote-already-cast = 你已經投過票了（{ $count }/{ $required }）
+vote-owner-bypass = { $emoji } { $user } 直接{ $action }了這首歌
+vote-passed = ✓ 投票通過（{ $count }/{ $required }），{ $action }這首歌
+vote-failed = ✗ 無法{ $action }，請稍後再試
+
+## 權限
+
+permissions = permissions
+    .description = 管理身分組可以使用的指令
+permissions-grant = grant
+    .description = 讓身分組擁有指定的能力
+    .capability = capability
+    .capability-description = 能力
+    .role = role
+    .role-description = 身分組
+permissions-revoke = revoke
+    .description = 移除身分組的能力
+    .capability = capability
+    .capability-description = 能力
+    .role = role
+    .role-description = 身分組
+permissions-list = list
+    .description = 列出每個能力對應的身分組
+permission-guild-only = ✗ 這個指令只能在伺服器中使用
+permission-owner-only = ✗ 這個指令會影響所有伺服器，只有機器人的擁有者可以使用
+permission-denied-managers = ✗ 這個指令需要 `{ $capability }` 權限，目前只開放給伺服器管理員
+permission-denied-roles = ✗ 這個指令需要 `{ $capability }` 權限，請向管理員申請以下身分組：{ $roles }
+permission-granted = ✓ { $role } 現在擁有 `{ $capability }` 權限
+permission-already-granted = { $role } 已經擁有 `{ $capability }` 權限
+permission-revoked = ✓ 已移除 { $role } 的 `{ $capability }` 權限
+permission-not-granted = { $role } 沒有 `{ $capability }` 權限
+permission-everyone = 所有人
+permission-managers = 伺服器管理員
+
+## 伺服器設定
+
+settings = settings
+    .description = 伺服器設定
+settings-view = view
+    .description = 顯示這個伺服器的設定
+settings-bitrate = bitrate
+    .description = 設定語音位元率，不填則交給 Discord 自動決定
+    .kbps = kbps
+    .kbps-description = 位元率（kbps）
+settings-idle_timeout = idle_timeout
+    .description = 設定暫停或頻道中沒有人多久後離開語音頻道，不填則使用預設值
+    .minutes = minutes
+    .minutes-description = 分鐘（0 表示不會自動離開）
+settings-idle_warning = idle_warning
+    .description = 設定離開語音頻道前多久發出警告，不填則使用預設值
+    .seconds = seconds
+    .seconds-description = 秒
+settings-language = language
+    .description = 設定機器人在這個伺服器使用的語言
+    .language = language
+    .language-description = 語言
+settings-title = ⚙️ 伺服器設定
+settings-field-announce = 換歌公告
+settings-field-bitrate = 語音位元率
+settings-field-language = 語言
+settings-field-idle-timeout = 閒置離開
+settings-field-idle-warning = 離開前警告
+settings-field-permissions = 權限設定
+settings-off = 關閉
+settings-auto = 自動
+settings-default = { $value }（預設）
+settings-unit-kbps = { $value } kbps
+settings-unit-minutes = { $value } 分鐘
+settings-unit-seconds = { $value } 秒
+settings-roles = { $count } 個身分組（使用 /permissions list 查看）
+settings-bitrate-set = ✓ 語音位元率已設為 { $kbps } kbps
+settings-bitrate-auto = ✓ 語音位元率已改回自動
+settings-idle-timeout-off = ✓ 已關閉閒置自動離開
+settings-idle-timeout-set = ✓ 閒置 { $minutes } 分鐘後會離開語音頻道
+settings-idle-warning-set = ✓ 離開前 { $seconds } 秒會發出警告
+settings-reset = ✓ 已改回預設值（{ $value }）
+settings-language-set = ✓ 語言已設為 { $language }
+
+## 猜歌遊戲
+
+quiz = quiz
+    .description = 根據目前播放的歌曲開始一輪猜歌遊戲
+quiz-nothing-playing = 目前沒有正在播放的歌曲
+quiz-join-voice = 請先加入語音頻道再開始遊戲
+quiz-already-running = 這個伺服器已經有一場遊戲正在進行
+quiz-bank-empty = 題庫為空，無法開始遊戲
+quiz-invalid-question = 這道題目的資料有誤，請通知管理員
+quiz-title = 🎮 猜歌遊戲
+quiz-random-question = ℹ️ 題庫中沒有目前曲目的題目，改為隨機出題
+quiz-question-track = 🎵 題目歌曲：《{ $track }》
+quiz-deadline = ⏱️ 作答截止 { $deadline }
+quiz-already-answered = 你已經作答過了
+quiz-answer-received = 已收到你的答案：{ $label }. { $answer }
+quiz-result-title = 🎮 猜歌遊戲 - 結果
+quiz-result-answer = ✅ 正確答案：{ $label }. { $answer }
+    🎵 《{ $track }》 - { $artist }
+quiz-result-correct = 答對
+quiz-result-wrong = 答錯
+quiz-result-ranking = ⚡ 答題速度排名
+quiz-nobody-correct = 沒有人答對
+quiz-ranking-entry = { $medal } { $user } { $seconds } 秒 · +{ $points } 分
+quiz-feedback-prompt = 這道題目出得如何？
+quiz-feedback-count = 這道題目出得如何？ 👍 { $likes } · 👎 { $dislikes }
+quiz-feedback-already-voted = 你已經評價過這道題目了
+quiz-feedback-removed = 這道題目已經不在題庫中
+quiz-generated-anime = 這是哪部動漫的主題曲？
+quiz-generated-artist = 這首歌是誰演唱的？
+quiz-generated-song-name = 這首歌的名字是？
+
+## 題庫管理
+
+quiz_import = quiz_import
+    .description = 從 JSON 檔案匯入題庫（擁有者）
+    .file = file
+    .file-description = 符合 quiz_bank.json 格式的檔案
+    .replace = replace
+    .replace-description = 取代整個題庫（預設以 id 合併）
+quiz_export = quiz_export
+    .description = 匯出目前的題庫（管理員）
+quiz_add = quiz_add
+    .description = 新增一道題目（擁有者）
+    .spotify_track_id = spotify_track_id
+    .spotify_track_id-description = Spotify Track ID
+    .track_name = track_name
+    .track_name-description = 歌名
+    .artist = artist
+    .artist-description = 演唱者
+    .quiz_type = quiz_type
+    .quiz_type-description = 題型
+    .question = question
+    .question-description = 題目
+    .correct_answer = correct_answer
+    .correct_answer-description = 正確答案
+    .wrong_1 = wrong_1
+    .wrong_1-description = 干擾選項 1
+    .wrong_2 = wrong_2
+    .wrong_2-description = 干擾選項 2
+    .wrong_3 = wrong_3
+    .wrong_3-description = 干擾選項 3
+    .difficulty = difficulty
+    .difficulty-description = 難度（預設普通）
+quiz_edit = quiz_edit
+    .description = 修改題目內容（擁有者）
+    .id = id
+    .id-description = 題目 id
+    .question = question
+    .question-description = 新的題目
+    .correct_answer = correct_answer
+    .correct_answer-description = 新的正確答案
+    .options = options
+    .options-description = 新的四個選項，以 | 分隔
+    .quiz_type = quiz_type
+    .quiz_type-description = 新的題型
+    .difficulty = difficulty
+    .difficulty-description = 新的難度
+quiz_remove = quiz_remove
+    .description = 從題庫移除題目（擁有者）
+    .id = id
+    .id-description = 題目 id
+quiz_report = quiz_report
+    .description = 產生題目品質報告（管理員）
+quiz-import-parse-failed = ✗ 無法解析題庫檔案：{ $error }
+quiz-imported = ✓ 已匯入 { $count } 道題目
+quiz-import-invalid = ✗ 題庫格式錯誤，未匯入任何題目：
+    { $problems }
+quiz-export-done = 📄 題庫匯出
+quiz-added = ✓ 已新增題目 `{ $id }`
+quiz-add-invalid = ✗ 題目格式錯誤：
+    { $problems }
+quiz-edited = ✓ 已更新題目 `{ $id }`
+quiz-edit-failed = ✗ 無法更新題目：
+    { $problems }
+quiz-removed = ✓ 已移除題目 `{ $id }`（《{ $track }》）
+quiz-not-found = ✗ 找不到題目 `{ $id }`
+quiz-report-title = 📋 題目品質報告
+quiz-report-clean = ✅ 沒有需要注意的題目
+quiz-report-entry = `{ $id }` 《{ $track }》 👍 { $likes } · 👎 { $dislikes } · { $plays } 次
+    　⚠️ { $flags }
+quiz-report-invalid-entry = `{ $id }` 《{ $track }》 不會出題
+    　✗ { $problems }
+quiz-report-more = …以及另外 { $count } 道題目
+quiz-report-flag-separator = 、
+quiz-flag-dislike-ratio = 倒讚比例 { $percent }%
+quiz-flag-too-easy = 答對率過高 { $percent }%
+quiz-flag-too-hard = 答對率過低 { $percent }%
+quiz-flag-low-score = 品質分數 { $score }
+quiz-problem-empty = { $field } 不能為空
+quiz-problem-option-count = options 必須剛好有 { $count } 個選項
+quiz-problem-blank-option = options 不能包含空白選項
+quiz-problem-duplicate-option = options 不能有重複的選項
+quiz-problem-answer-not-in-options = correct_answer 必須是 options 之一
+quiz-problem-out-of-range = { $field } 必須介於 0 到 1
+quiz-problem-duplicate-id = id 重複
+quiz-problem-unsupported-version = 不支援的題庫版本 { $version }
+quiz-report-total = 題目總數
+quiz-report-flagged = 需要注意
+quiz-report-invalid = 不會出題
+
+## 分數
+
+leaderboard = leaderboard
+    .description = 顯示猜歌遊戲排行榜
+leaderboard-title = 🏆 猜歌排行榜
+leaderboard-entry = `{ $rank }.` { $user } **{ $points }** 分 · 正確率 { $accuracy }% · 最佳連勝 { $streak }
+leaderboard-empty = 這個伺服器還沒有人玩過猜歌遊戲
+stats = stats
+    .description = 顯示個人猜歌統計
+    .user = user
+    .user-description = 要查看的使用者（預設為自己）
+stats_export = stats_export
+    .description = 匯出本伺服器的猜歌分數（管理員）
+stats_reset = stats_reset
+    .description = 重設本伺服器或單一使用者的猜歌分數（管理員）
+    .user = user
+    .user-description = 只重設此使用者（預設為整個伺服器）
+stats-empty = { $user } 還沒有玩過猜歌遊戲
+stats-title = 📊 { $user } 的猜歌統計
+stats-field-points = 總分
+stats-field-games = 遊戲次數
+stats-field-accuracy = 正確率
+stats-field-answers = 答對 / 答錯
+stats-field-answer-time = 平均作答時間
+stats-field-streak = 連勝（目前 / 最佳）
+stats-seconds = { $seconds } 秒
+stats-export-done = 📄 猜歌分數匯出
+stats-reset-user = ✓ 已重設 { $user } 的猜歌分數
+stats-reset-guild = ✓ 已重設本伺服器的所有猜歌分數
+
+## Spotify 認證
+
+spotify_login = spotify_login
+    .description = 透過 Spotify Connect 的 Discovery 重新認證 Spotify 帳號
+    .force = force
+    .force-description = 已經有憑證時也重新認證（例如更換帳號）
+auth-in-progress = 認證已經在進行中，請在 Spotify App 中選擇機器人的裝置
+auth-already-authenticated = 已經完成 Spotify 認證，若要更換帳號請加上 `force: True`
+auth-started = 🔑 請在 5 分鐘內打開 Spotify App，在裝置列表中選擇 **{ $device }**
+auth-failed = ✗ Spotify 認證失敗或逾時，請再使用一次 /spotify_login
+auth-failed-kept = ✗ Spotify 認證失敗或逾時，繼續使用原本的帳號
+auth-done = ✓ Spotify 認證完成，加入語音頻道即可使用 Spotify Connect
+connect-needs-auth = Spotify 尚未認證，請機器人的擁有者使用 /spotify_login
+
+## 音訊快取
+
+cache = cache
+    .description = 管理 Spotify 音訊檔案快取
+cache-stats = stats
+    .description = 顯示音訊檔案快取的使用量與命中率
+cache-clear = clear
+    .description = 刪除所有快取的音訊檔案（擁有者）
+cache-title = 💾 音訊檔案快取
+cache-field-size = 使用量
+cache-field-files = 檔案數
+cache-field-hit-rate = 命中率
+cache-field-evicted = 因大小上限清除的檔案
+cache-size-limited = { $size } / { $limit }（{ $percent }%）
+cache-size-unlimited = { $size }（無上限）
+cache-hit-rate = { $percent }%（命中 { $hits } 次，未命中 { $misses } 次）
+cache-hit-rate-none = 啟動後還沒有播放過
+cache-footer = 命中率與清除數從機器人啟動後開始計算
+cache-disabled = ✗ 沒有設定快取目錄
+cache-cleared = ✓ 已刪除 { $count } 個音訊檔案（{ $size }）
+cache-cleared-partial = ⚠️ 已刪除 { $count } 個音訊檔案（{ $size }），{ $failed } 個無法刪除